@@ -0,0 +1,225 @@
+use std::io::{self, Write};
+
+use crossterm::{
+    cursor, queue,
+    style::{
+        Attribute, Attributes, Color, ContentStyle, Print, ResetColor, SetAttribute,
+        SetBackgroundColor, SetForegroundColor, SetUnderlineColor,
+    },
+    terminal,
+};
+
+use crate::{
+    color_depth::{ColorDepth, ansi_16_sgr_code, dither_offset},
+    renderer::{Cell, CellWidth, ScreenBuffer, build_crossterm_content_style},
+};
+
+/// Attributes with a code of their own to turn them off, paired with it.
+const TOGGLED_ATTRIBUTES: [(Attribute, Attribute); 5] = [
+    (Attribute::Italic, Attribute::NoItalic),
+    (Attribute::Underlined, Attribute::NoUnderline),
+    (Attribute::SlowBlink, Attribute::NoBlink),
+    (Attribute::Reverse, Attribute::NoReverse),
+    (Attribute::CrossedOut, Attribute::NotCrossedOut),
+];
+
+/// Output device for the cells that changed between two frames. A run never starts on
+/// the continuation cell of a double width glyph.
+pub trait Backend {
+    /// Draws `cells` left to right, starting at `x`, `y`.
+    fn draw_run(&mut self, x: u16, y: u16, cells: &[Cell]) -> io::Result<()>;
+    /// Asks the device to stay at the game's screen size.
+    fn resize(&mut self, width: u16, height: u16) -> io::Result<()>;
+    /// Called once per frame after every changed cell has been drawn.
+    fn flush(&mut self) -> io::Result<()>;
+}
+
+/// Draws to a terminal through crossterm commands. Colors and boldness are only sent when
+/// they differ from what the terminal is already set to.
+pub struct CrosstermBackend<W: Write> {
+    writer: W,
+    color_depth: ColorDepth,
+    dither: bool,
+    /// Style the terminal is set to, unknown at the start of every frame.
+    current_style: Option<ContentStyle>,
+}
+
+impl<W: Write> CrosstermBackend<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            color_depth: ColorDepth::TrueColor,
+            dither: false,
+            current_style: None,
+        }
+    }
+
+    pub fn with_color_depth(mut self, color_depth: ColorDepth) -> Self {
+        self.color_depth = color_depth;
+        self
+    }
+
+    /// Dithers bg colors the color depth can't show, which smooths out shaded areas.
+    pub fn with_dither(mut self, value: bool) -> Self {
+        self.dither = value;
+        self
+    }
+
+    /// For output outside of frames, like setting the terminal up and restoring it.
+    pub fn writer_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Named colors go out as plain 16 color SGR codes, everything else through crossterm.
+    fn queue_color(&mut self, color: Color, is_background: bool) -> io::Result<()> {
+        match ansi_16_sgr_code(color, is_background) {
+            Some(code) => write!(self.writer, "\x1b[{code}m"),
+            None if is_background => queue!(self.writer, SetBackgroundColor(color)),
+            None => queue!(self.writer, SetForegroundColor(color)),
+        }
+    }
+
+    fn set_style(&mut self, style: ContentStyle) -> io::Result<()> {
+        let previous: Option<ContentStyle> = self.current_style.replace(style);
+
+        if previous.is_none_or(|previous| previous.foreground_color != style.foreground_color)
+            && let Some(color) = style.foreground_color
+        {
+            self.queue_color(color, false)?;
+        }
+
+        if previous.is_none_or(|previous| previous.background_color != style.background_color)
+            && let Some(color) = style.background_color
+        {
+            self.queue_color(color, true)?;
+        }
+
+        // Nothing is set after the reset that ends every frame
+        let previous_attributes: Attributes = previous
+            .map(|previous| previous.attributes)
+            .unwrap_or_default();
+
+        // Bold and dim are both turned off by the same code
+        let intensity_changed: bool = [Attribute::Bold, Attribute::Dim].iter().any(|&attribute| {
+            previous_attributes.has(attribute) != style.attributes.has(attribute)
+        });
+        if intensity_changed {
+            let was_intense: bool = [Attribute::Bold, Attribute::Dim]
+                .iter()
+                .any(|&attribute| previous_attributes.has(attribute));
+            if was_intense {
+                queue!(self.writer, SetAttribute(Attribute::NormalIntensity))?;
+            }
+            for attribute in [Attribute::Bold, Attribute::Dim] {
+                if style.attributes.has(attribute) {
+                    queue!(self.writer, SetAttribute(attribute))?;
+                }
+            }
+        }
+
+        for (on, off) in TOGGLED_ATTRIBUTES {
+            let is_on: bool = style.attributes.has(on);
+            if previous_attributes.has(on) != is_on {
+                queue!(self.writer, SetAttribute(if is_on { on } else { off }))?;
+            }
+        }
+
+        let previous_underline_color: Option<Color> =
+            previous.and_then(|previous| previous.underline_color);
+        if previous_underline_color != style.underline_color {
+            let color: Color = style.underline_color.unwrap_or(Color::Reset);
+            queue!(self.writer, SetUnderlineColor(color))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<W: Write> Backend for CrosstermBackend<W> {
+    fn draw_run(&mut self, x: u16, y: u16, cells: &[Cell]) -> io::Result<()> {
+        queue!(self.writer, cursor::MoveTo(x, y))?;
+
+        for (cell_x, cell) in (x..).zip(cells) {
+            // Already covered by the wide glyph printed right before it
+            if cell.width == CellWidth::Continuation {
+                continue;
+            }
+
+            let bg_dither: f32 = if self.dither {
+                dither_offset(cell_x, y)
+            } else {
+                0.0
+            };
+            self.set_style(build_crossterm_content_style(
+                cell,
+                self.color_depth,
+                bg_dither,
+            ))?;
+            queue!(self.writer, Print(cell.printed_text()))?;
+        }
+
+        Ok(())
+    }
+
+    fn resize(&mut self, width: u16, height: u16) -> io::Result<()> {
+        queue!(self.writer, terminal::SetSize(width, height))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        queue!(self.writer, ResetColor)?;
+        self.current_style = None;
+        self.writer.flush()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum BackendOp {
+    DrawRun { x: u16, y: u16, cells: Vec<Cell> },
+    Resize { width: u16, height: u16 },
+    Flush,
+}
+
+/// Keeps the grid a terminal would show plus every operation it received, for tests and
+/// headless tools.
+pub struct MemoryBackend {
+    pub grid: ScreenBuffer,
+    pub ops: Vec<BackendOp>,
+}
+
+impl MemoryBackend {
+    /// Starts out like a fresh `Screen`, so applying its diffs reproduces the composed frame.
+    pub fn new(width: u16, height: u16, default_bg: (u8, u8, u8)) -> Self {
+        Self {
+            grid: ScreenBuffer::new(width, height, default_bg),
+            ops: vec![],
+        }
+    }
+}
+
+impl Backend for MemoryBackend {
+    fn draw_run(&mut self, x: u16, y: u16, cells: &[Cell]) -> io::Result<()> {
+        for (cell_x, cell) in (x..).zip(cells) {
+            if cell_x < self.grid.width && y < self.grid.height {
+                let index: usize = y as usize * self.grid.width as usize + cell_x as usize;
+                self.grid.cells[index] = cell.clone();
+            }
+        }
+
+        self.ops.push(BackendOp::DrawRun {
+            x,
+            y,
+            cells: cells.to_vec(),
+        });
+        Ok(())
+    }
+
+    fn resize(&mut self, width: u16, height: u16) -> io::Result<()> {
+        self.ops.push(BackendOp::Resize { width, height });
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.ops.push(BackendOp::Flush);
+        Ok(())
+    }
+}
@@ -3,10 +3,35 @@ use unicode_width::UnicodeWidthStr;
 use crate::{
     card_ops::CardDragState,
     context::Context,
-    renderer::{DrawCall, Hsl, Rgba, RichText, draw_rect, point_in_rect},
+    reel_editor::ReelEditOp,
+    renderer::{DrawCall, Hsl, Layer, Rgba, RichText, draw_rect, point_in_rect},
 };
 
+/// What a button does, so it can be found without depending on its label.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ButtonId {
+    Spin,
+    Luck,
+    Play,
+    Burn,
+    TakeColumn { index: usize },
+    NewRun,
+    BuyShopItem { index: usize },
+    Reroll,
+    OpenReelEditor,
+    LeaveShop,
+    SelectReelCard { index: usize },
+    CycleEditedColumn { step: isize },
+    CycleNewCardRank { step: isize },
+    CycleNewCardSuit,
+    ReelEdit(ReelEditOp),
+    CloseReelEditor,
+    Resume,
+    DeclineResume,
+}
+
 pub struct Button {
+    pub id: ButtonId,
     pub x: u16,
     pub y: u16,
     pub w: u16,
@@ -14,7 +39,7 @@ pub struct Button {
     pub text: String,
     pub color: Rgba,
     pub on_click: Box<dyn Fn(&mut Context)>,
-    pub enabled_when: fn(&Context) -> bool,
+    pub enabled_when: Box<dyn Fn(&Context) -> bool>,
     pub allow_rmb: bool,
 }
 
@@ -43,12 +68,17 @@ pub fn draw_button(draw_queue: &mut Vec<DrawCall>, ctx: &Context, button: &Butto
 
     let draw_x = button.x + 1 + offset;
 
+    // Disabled labels fade into the darkened button
+    let is_enabled: bool = (button.enabled_when)(ctx);
+
     draw_queue.push(DrawCall {
         x: draw_x,
         y: button.y,
         rich_text: RichText::new(&button.text)
             .with_fg(Rgba::from_f32(0.0, 0.0, 0.0, 1.0))
-            .with_bold(true),
+            .with_bold(is_enabled)
+            .with_dim(!is_enabled),
+        layer: Layer::default(),
     });
 }
 
@@ -1,12 +1,12 @@
 use crate::{
-    constants::{DEFAULT_CARD_BG_COLOR, SUIT_COLOR_BLACK, SUIT_COLOR_RED},
-    renderer::{DrawCall, Rgba, RichText},
+    constants::{DEFAULT_CARD_BG_COLOR, SUIT_COLOR_BLACK, SUIT_COLOR_RED, WILD_CARD_COLOR},
+    renderer::{DrawCall, Layer, Rgba, RichText},
 };
 
 pub const BIG_CARD_WIDTH: u16 = 3;
 pub const BIG_CARD_HEIGHT: u16 = 3;
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash, Debug)]
 pub enum Suit {
     Spade,
     Heart,
@@ -38,7 +38,7 @@ impl Suit {
     }
 }
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash, Debug)]
 pub enum Rank {
     Ace,
     King,
@@ -112,17 +112,181 @@ impl Rank {
     }
 }
 
-#[derive(Clone, Copy)]
+/// Where a card is when a hand gets played, enhancements trigger on different placements.
+#[derive(Clone, Copy, PartialEq)]
+pub enum CardPlacement {
+    /// Part of the poker hand.
+    Scoring,
+    /// On the table, whether it scores or not.
+    Played,
+    /// Still in hand.
+    Held,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug)]
+pub enum Enhancement {
+    Foil,
+    Gold,
+    Glass,
+    Stone,
+    Steel,
+}
+
+impl Enhancement {
+    pub fn iter() -> std::array::IntoIter<Enhancement, 5> {
+        [
+            Enhancement::Foil,
+            Enhancement::Gold,
+            Enhancement::Glass,
+            Enhancement::Stone,
+            Enhancement::Steel,
+        ]
+        .into_iter()
+    }
+
+    /// Marker shown in the middle row of big cards.
+    pub fn repr(&self) -> &'static str {
+        match self {
+            Enhancement::Foil => "✦",
+            Enhancement::Gold => "$",
+            Enhancement::Glass => "◇",
+            Enhancement::Stone => "▪",
+            Enhancement::Steel => "■",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Enhancement::Foil => "+20 chips when scoring",
+            Enhancement::Gold => "+8 coins when held during a play",
+            Enhancement::Glass => "x1.5 mult when scoring, may break",
+            Enhancement::Stone => "+30 chips when played at all",
+            Enhancement::Steel => "x1.25 mult when held in a play",
+        }
+    }
+
+    /// Chips added to a played hand.
+    pub fn flat_bonus(&self, placement: CardPlacement) -> i32 {
+        match (self, placement) {
+            (Enhancement::Foil, CardPlacement::Scoring) => 20,
+            (Enhancement::Stone, CardPlacement::Played) => 30,
+            _ => 0,
+        }
+    }
+
+    /// Coins paid on top of a played hand's score.
+    pub fn coin_bonus(&self, placement: CardPlacement) -> i32 {
+        match (self, placement) {
+            (Enhancement::Gold, CardPlacement::Held) => 8,
+            _ => 0,
+        }
+    }
+
+    /// Factor the mult of a played hand is multiplied by.
+    pub fn multiplier(&self, placement: CardPlacement) -> f32 {
+        match (self, placement) {
+            (Enhancement::Glass, CardPlacement::Scoring) => 1.5,
+            (Enhancement::Steel, CardPlacement::Held) => 1.25,
+            _ => 1.0,
+        }
+    }
+
+    /// Card background, replacing `DEFAULT_CARD_BG_COLOR`.
+    pub fn bg_color(&self) -> Rgba {
+        match self {
+            Enhancement::Foil => Rgba::from_u8(215, 190, 255, 1.0),
+            Enhancement::Gold => Rgba::from_u8(255, 210, 90, 1.0),
+            Enhancement::Glass => Rgba::from_u8(170, 240, 230, 1.0),
+            Enhancement::Stone => Rgba::from_u8(165, 160, 150, 1.0),
+            Enhancement::Steel => Rgba::from_u8(175, 190, 210, 1.0),
+        }
+    }
+}
+
+/// What a wild card can stand in for, the poker hand evaluator picks the best fit.
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug)]
+pub enum WildKind {
+    Suit,
+    Rank,
+    Any,
+}
+
+impl WildKind {
+    pub fn iter() -> std::array::IntoIter<WildKind, 3> {
+        [WildKind::Suit, WildKind::Rank, WildKind::Any].into_iter()
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Hash)]
 pub struct Card {
     pub suit: Suit,
     pub rank: Rank,
+    pub enhancement: Option<Enhancement>,
+    pub wild: Option<WildKind>,
+}
+
+impl Card {
+    pub fn new(suit: Suit, rank: Rank) -> Self {
+        Self {
+            suit,
+            rank,
+            enhancement: None,
+            wild: None,
+        }
+    }
+
+    pub fn has_wild_suit(&self) -> bool {
+        matches!(self.wild, Some(WildKind::Suit | WildKind::Any))
+    }
+
+    pub fn has_wild_rank(&self) -> bool {
+        matches!(self.wild, Some(WildKind::Rank | WildKind::Any))
+    }
+
+    /// Whether `resolved` is this card with its wild parts filled in by the evaluator.
+    pub fn resolves_to(&self, resolved: &Card) -> bool {
+        self.wild == resolved.wild
+            && self.enhancement == resolved.enhancement
+            && (self.has_wild_suit() || self.suit == resolved.suit)
+            && (self.has_wild_rank() || self.rank == resolved.rank)
+    }
+
+    /// Wild parts of a card are drawn as `*`.
+    pub fn suit_repr(&self) -> &'static str {
+        if self.has_wild_suit() {
+            "*"
+        } else {
+            self.suit.repr()
+        }
+    }
+
+    pub fn rank_repr(&self) -> &'static str {
+        if self.has_wild_rank() {
+            "*"
+        } else {
+            self.rank.repr()
+        }
+    }
+
+    pub fn fg_color(&self) -> Rgba {
+        if self.wild.is_some() {
+            WILD_CARD_COLOR
+        } else {
+            self.suit.color()
+        }
+    }
+
+    pub fn bg_color(&self) -> Rgba {
+        self.enhancement
+            .map_or(DEFAULT_CARD_BG_COLOR, |enhancement| enhancement.bg_color())
+    }
 }
 
 pub fn draw_calls_playing_card_small(x: u16, y: u16, card: &Card) -> DrawCall {
-    let suit_repr: &'static str = card.suit.repr();
-    let rank_repr: &'static str = card.rank.repr();
-    let suit_color: Rgba = card.suit.color();
-    let bg_color: Rgba = DEFAULT_CARD_BG_COLOR;
+    let suit_repr: &'static str = card.suit_repr();
+    let rank_repr: &'static str = card.rank_repr();
+    let fg_color: Rgba = card.fg_color();
+    let bg_color: Rgba = card.bg_color();
 
     let text: String = format!("{suit_repr}{rank_repr:>2}");
 
@@ -130,22 +294,29 @@ pub fn draw_calls_playing_card_small(x: u16, y: u16, card: &Card) -> DrawCall {
         x,
         y,
         rich_text: RichText::new(text)
-            .with_fg(suit_color)
+            .with_fg(fg_color)
             .with_bg(bg_color)
             .with_bold(true),
+        layer: Layer::default(),
     }
 }
 
 pub fn draw_calls_playing_card_big(x: i16, y: i16, card: &Card) -> Vec<DrawCall> {
     let mut draw_calls: Vec<DrawCall> = vec![];
 
-    let suit_repr: &'static str = card.suit.repr();
-    let rank_repr: &'static str = card.rank.repr();
-    let suit_color: Rgba = card.suit.color();
-    let bg_color: Rgba = DEFAULT_CARD_BG_COLOR;
+    let suit_repr: &'static str = card.suit_repr();
+    let rank_repr: &'static str = card.rank_repr();
+    let fg_color: Rgba = card.fg_color();
+    let bg_color: Rgba = card.bg_color();
 
     // Choose pattern based on rank
     let pattern: [&str; 3] = match card.rank {
+        #[rustfmt::skip]
+        _ if card.has_wild_rank() => [
+            "<<S",
+            " S ",
+            "S>>"
+        ],
         #[rustfmt::skip]
         Rank::Ace => [
             "<< ",
@@ -180,6 +351,15 @@ pub fn draw_calls_playing_card_big(x: i16, y: i16, card: &Card) -> Vec<DrawCall>
         text_row = text_row.replace(">>", &format!("{rank_repr:>2}"));
         text_row = text_row.replace("S", suit_repr);
 
+        // Enhancements are marked on the left of the middle row, which is always blank
+        if let (1, Some(enhancement)) = (row_index, card.enhancement) {
+            text_row = format!(
+                "{}{}",
+                enhancement.repr(),
+                text_row.chars().skip(1).collect::<String>()
+            );
+        }
+
         // x clipping
         if x < 0 {
             let chars_to_trim = -x as usize;
@@ -193,7 +373,7 @@ pub fn draw_calls_playing_card_big(x: i16, y: i16, card: &Card) -> Vec<DrawCall>
         }
 
         let rich_text = RichText::new(text_row)
-            .with_fg(suit_color)
+            .with_fg(fg_color)
             .with_bg(bg_color)
             .with_bold(true);
 
@@ -201,6 +381,7 @@ pub fn draw_calls_playing_card_big(x: i16, y: i16, card: &Card) -> Vec<DrawCall>
             x: line_x as u16,
             y: line_y as u16,
             rich_text,
+            layer: Layer::default(),
         });
     }
 
@@ -209,6 +390,6 @@ pub fn draw_calls_playing_card_big(x: i16, y: i16, card: &Card) -> Vec<DrawCall>
 
 pub fn standard_52_deck() -> Vec<Card> {
     Suit::iter()
-        .flat_map(|suit| Rank::iter().map(move |rank| Card { suit, rank }))
+        .flat_map(|suit| Rank::iter().map(move |rank| Card::new(suit, rank)))
         .collect()
 }
@@ -3,7 +3,8 @@ use crate::{
     context::Context,
     hand::{HAND_CARD_X_SPACING, HAND_ORIGIN_X, HAND_ORIGIN_Y},
     poker_hand::update_current_poker_hand,
-    renderer::{DrawCall, Rgba, draw_rect, point_in_rect},
+    renderer::{DrawCall, Layer, Rgba, draw_in_layer, draw_rect, point_in_rect},
+    scoring::scoring_in_progress,
     table::{TABLE_CARD_X_SPACING, TABLE_ORIGIN_X, TABLE_ORIGIN_Y},
 };
 
@@ -16,7 +17,7 @@ pub enum CardDragState {
     },
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub enum CardDragAndDropLocation {
     Hand { index: usize },
     Table { index: usize },
@@ -33,8 +34,7 @@ pub fn get_valid_drop_destination(
         let y1: u16 = TABLE_ORIGIN_Y;
 
         let destination_is_source: bool = matches!(source_location, CardDragAndDropLocation::Table { index } if *index == table_slot_index);
-        // let destination_is_locked: bool = !matches!(ctx.slots.state, SlotsState::Idle);
-        let destination_is_locked: bool = false;
+        let destination_is_locked: bool = scoring_in_progress(ctx);
         let hitbox_check_failed: bool = !point_in_rect(
             ctx.mouse.x,
             ctx.mouse.y,
@@ -134,18 +134,21 @@ pub fn draw_dragged_card(draw_queue: &mut Vec<DrawCall>, card: &Card, ctx: &mut
     let anchor_x: i16 = ctx.mouse.x as i16 - 1;
     let anchor_y: i16 = ctx.mouse.y as i16 - 2;
 
-    // Shadow
-    draw_rect(
-        draw_queue,
-        anchor_x - 1,
-        anchor_y + 1,
-        BIG_CARD_WIDTH,
-        BIG_CARD_HEIGHT,
-        Rgba::from_f32(0.0, 0.0, 0.0, 0.13),
-    );
-
-    // Card
-    draw_queue.extend(draw_calls_playing_card_big(anchor_x, anchor_y, card));
+    // Above everything but debug overlays, wherever in the frame it's drawn
+    draw_in_layer(draw_queue, Layer::DragPreview, |draw_queue| {
+        // Shadow
+        draw_rect(
+            draw_queue,
+            anchor_x - 1,
+            anchor_y + 1,
+            BIG_CARD_WIDTH,
+            BIG_CARD_HEIGHT,
+            Rgba::from_f32(0.0, 0.0, 0.0, 0.13),
+        );
+
+        // Card
+        draw_queue.extend(draw_calls_playing_card_big(anchor_x, anchor_y, card));
+    });
     update_current_poker_hand(ctx);
 }
 
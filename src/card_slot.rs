@@ -17,7 +17,6 @@ pub fn build_card_slots(
     count: usize,
 ) -> Vec<CardSlot> {
     (0..count)
-        .into_iter()
         .map(|index| CardSlot {
             x: origin_x + index as u16 * x_spacing,
             y: origin_y,
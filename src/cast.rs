@@ -0,0 +1,82 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// Passes terminal output through while recording it as an asciicast v2 file,
+/// one output event per flush, so every frame becomes one event.
+pub struct CastWriter<W: Write> {
+    inner: W,
+    file: BufWriter<File>,
+    started_at: Instant,
+    pending: Vec<u8>,
+}
+
+pub fn start_cast<W: Write>(
+    inner: W,
+    path: &Path,
+    width: u16,
+    height: u16,
+) -> io::Result<CastWriter<W>> {
+    let mut file: BufWriter<File> = BufWriter::new(File::create(path)?);
+    let timestamp: u64 = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+
+    writeln!(
+        file,
+        "{{\"version\": 2, \"width\": {width}, \"height\": {height}, \"timestamp\": {timestamp}}}"
+    )?;
+
+    Ok(CastWriter {
+        inner,
+        file,
+        started_at: Instant::now(),
+        pending: vec![],
+    })
+}
+
+impl<W: Write> Write for CastWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written: usize = self.inner.write(buf)?;
+        self.pending.extend_from_slice(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()?;
+
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+
+        let elapsed: f64 = self.started_at.elapsed().as_secs_f64();
+        let data: String = json_escape(&String::from_utf8_lossy(&self.pending));
+        writeln!(self.file, "[{elapsed:.6}, \"o\", \"{data}\"]")?;
+        self.pending.clear();
+        self.file.flush()
+    }
+}
+
+fn json_escape(text: &str) -> String {
+    let mut escaped: String = String::with_capacity(text.len());
+
+    for char in text.chars() {
+        match char {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            // Covers ESC, which starts every color and cursor sequence
+            char if (char as u32) < 0x20 || char == '\u{7f}' => {
+                escaped.push_str(&format!("\\u{:04x}", char as u32));
+            }
+            char => escaped.push(char),
+        }
+    }
+
+    escaped
+}
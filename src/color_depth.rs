@@ -0,0 +1,158 @@
+use std::env;
+
+use crossterm::style::Color;
+
+/// Colors the terminal can show, frames are quantized down to them on output.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColorDepth {
+    TrueColor,
+    /// The xterm-256 palette.
+    Ansi256,
+    /// The 16 standard ANSI colors.
+    Ansi16,
+}
+
+impl ColorDepth {
+    /// Parses the value of `--colors`.
+    pub fn parse(value: &str) -> Option<ColorDepth> {
+        match value {
+            "truecolor" | "24bit" => Some(ColorDepth::TrueColor),
+            "256" => Some(ColorDepth::Ansi256),
+            "16" => Some(ColorDepth::Ansi16),
+            _ => None,
+        }
+    }
+}
+
+/// Guesses the color depth from `COLORTERM` and `TERM`, the same variables most
+/// terminal apps go by. Without a `TERM` to go by, truecolor is assumed like on Windows.
+pub fn detect_color_depth() -> ColorDepth {
+    let colorterm: String = env::var("COLORTERM").unwrap_or_default();
+    if colorterm == "truecolor" || colorterm == "24bit" {
+        return ColorDepth::TrueColor;
+    }
+
+    match env::var("TERM") {
+        Err(_) => ColorDepth::TrueColor,
+        Ok(term) if term.ends_with("-direct") || term.contains("truecolor") => {
+            ColorDepth::TrueColor
+        }
+        Ok(term) if term.contains("256color") => ColorDepth::Ansi256,
+        Ok(_) => ColorDepth::Ansi16,
+    }
+}
+
+/// 4x4 Bayer matrix, the thresholds of ordered dithering.
+const BAYER_MATRIX: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// Per cell offset in `-0.5..0.5`, added to a color before quantizing it to dither it.
+pub fn dither_offset(x: u16, y: u16) -> f32 {
+    (BAYER_MATRIX[y as usize % 4][x as usize % 4] as f32 + 0.5) / 16.0 - 0.5
+}
+
+/// Channel levels of the 6x6x6 color cube in the xterm-256 palette.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// The 16 ANSI colors with xterm's default values.
+const ANSI_16_PALETTE: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::DarkRed, (205, 0, 0)),
+    (Color::DarkGreen, (0, 205, 0)),
+    (Color::DarkYellow, (205, 205, 0)),
+    (Color::DarkBlue, (0, 0, 238)),
+    (Color::DarkMagenta, (205, 0, 205)),
+    (Color::DarkCyan, (0, 205, 205)),
+    (Color::Grey, (229, 229, 229)),
+    (Color::DarkGrey, (127, 127, 127)),
+    (Color::Red, (255, 0, 0)),
+    (Color::Green, (0, 255, 0)),
+    (Color::Yellow, (255, 255, 0)),
+    (Color::Blue, (92, 92, 255)),
+    (Color::Magenta, (255, 0, 255)),
+    (Color::Cyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+fn distance_squared(a: (u8, u8, u8), b: (u8, u8, u8)) -> i32 {
+    let dr: i32 = a.0 as i32 - b.0 as i32;
+    let dg: i32 = a.1 as i32 - b.1 as i32;
+    let db: i32 = a.2 as i32 - b.2 as i32;
+    dr * dr + dg * dg + db * db
+}
+
+fn nearest_cube_level_index(channel: u8) -> usize {
+    (0..CUBE_LEVELS.len())
+        .min_by_key(|&index| (CUBE_LEVELS[index] as i32 - channel as i32).abs())
+        .expect("Cube levels aren't empty")
+}
+
+fn nearest_ansi_256(rgb: (u8, u8, u8)) -> u8 {
+    let (r, g, b) = rgb;
+    let cube_indexes: [usize; 3] = [
+        nearest_cube_level_index(r),
+        nearest_cube_level_index(g),
+        nearest_cube_level_index(b),
+    ];
+    let cube_rgb: (u8, u8, u8) = (
+        CUBE_LEVELS[cube_indexes[0]],
+        CUBE_LEVELS[cube_indexes[1]],
+        CUBE_LEVELS[cube_indexes[2]],
+    );
+    let cube_value: u8 = 16 + (cube_indexes[0] * 36 + cube_indexes[1] * 6 + cube_indexes[2]) as u8;
+
+    // The grayscale ramp runs from 8 to 238 in steps of 10
+    let average: i32 = (r as i32 + g as i32 + b as i32) / 3;
+    let gray_index: u8 = ((average - 8 + 5) / 10).clamp(0, 23) as u8;
+    let gray_level: u8 = 8 + gray_index * 10;
+    let gray_value: u8 = 232 + gray_index;
+
+    if distance_squared(rgb, (gray_level, gray_level, gray_level)) < distance_squared(rgb, cube_rgb)
+    {
+        gray_value
+    } else {
+        cube_value
+    }
+}
+
+fn nearest_ansi_16(rgb: (u8, u8, u8)) -> Color {
+    ANSI_16_PALETTE
+        .iter()
+        .min_by_key(|(_, palette_rgb)| distance_squared(rgb, *palette_rgb))
+        .map(|(color, _)| *color)
+        .expect("Palette isn't empty")
+}
+
+fn nudge_rgb(rgb: (u8, u8, u8), dither: f32, spread: f32) -> (u8, u8, u8) {
+    let nudge = |channel: u8| (channel as f32 + dither * spread).round().clamp(0.0, 255.0) as u8;
+    (nudge(rgb.0), nudge(rgb.1), nudge(rgb.2))
+}
+
+/// Closest color the depth can show. `dither` comes from `dither_offset` and nudges the
+/// color by up to half the palette's spacing, 0 picks the nearest color.
+pub fn quantize_color(rgb: (u8, u8, u8), depth: ColorDepth, dither: f32) -> Color {
+    // Spreads are roughly the distance between neighboring palette colors
+    match depth {
+        ColorDepth::TrueColor => {
+            let (r, g, b) = rgb;
+            Color::Rgb { r, g, b }
+        }
+        ColorDepth::Ansi256 => Color::AnsiValue(nearest_ansi_256(nudge_rgb(rgb, dither, 40.0))),
+        ColorDepth::Ansi16 => nearest_ansi_16(nudge_rgb(rgb, dither, 128.0)),
+    }
+}
+
+/// SGR code of one of the 16 ANSI colors, 30-37 and 90-97 as fg, 40-47 and 100-107 as bg.
+/// crossterm sends these colors as `38;5;N` palette indexes, which only 256 color
+/// terminals understand. `None` for any other color.
+pub fn ansi_16_sgr_code(color: Color, is_background: bool) -> Option<u8> {
+    let index: u8 = ANSI_16_PALETTE
+        .iter()
+        .position(|(palette_color, _)| *palette_color == color)? as u8;
+    let fg_code: u8 = if index < 8 {
+        30 + index
+    } else {
+        90 + index - 8
+    };
+
+    Some(if is_background { fg_code + 10 } else { fg_code })
+}
@@ -23,6 +23,13 @@ pub static SUIT_COLOR_RED: Rgba = Rgba {
     a: 1.0,
 };
 
+pub static WILD_CARD_COLOR: Rgba = Rgba {
+    r: 150,
+    g: 40,
+    b: 200,
+    a: 1.0,
+};
+
 pub static DEFAULT_CARD_BG_COLOR: Rgba = Rgba {
     r: 255,
     g: 255,
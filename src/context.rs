@@ -7,10 +7,18 @@ use crate::{
     card_slot::{CardSlot, build_card_slots},
     fps_counter::FPSCounter,
     hand::{HAND_CARD_X_SPACING, HAND_ORIGIN_X, HAND_ORIGIN_Y, HAND_SLOT_COUNT},
+    hint::Hint,
+    odds::SpinOdds,
     poker_hand::PokerHand,
+    reel_editor::ReelEditor,
+    relic::Relic,
     renderer::Screen,
+    rng::{GameRng, build_rng, random_seed},
+    run::Run,
+    scoring::ScoringAnimation,
     shader::{build_gamma_lut, build_vignette_lut},
-    slot_machine::{SlotMachine, SlotMachineColumn},
+    shop::Shop,
+    slot_machine::{SlotMachine, SlotMachineColumn, build_reel},
     table::{TABLE_CARD_X_SPACING, TABLE_ORIGIN_X, TABLE_ORIGIN_Y, TABLE_SLOT_COUNT},
 };
 
@@ -18,12 +26,31 @@ pub struct Context {
     pub score: i32,
     pub coins: i32,
     pub luck: i32,
+    /// Whether the next spin spends luck to nudge the reels.
+    pub luck_armed: bool,
+    pub dry_spin_streak: i32,
     pub game_time: f32,
+    pub seed: u64,
+    pub rng: GameRng,
 
     pub poker_hand: Option<PokerHand>,
+    pub hand_levels: HashMap<PokerHand, i32>,
+    pub hand_play_counts: HashMap<PokerHand, i32>,
+    pub paytable_visible: bool,
+    /// Shown on startup when there's a saved run to resume.
+    pub resume_prompt_open: bool,
+    /// Recorded, replayed and seeded sessions never write the save.
+    pub save_disabled: bool,
+    pub scoring: Option<ScoringAnimation>,
+    pub hint: Option<Hint>,
+    pub spin_odds: Option<SpinOdds>,
     pub table_card_slots: Vec<CardSlot>,
     pub hand_card_slots: Vec<CardSlot>,
     pub slot_machine: SlotMachine,
+    pub run: Run,
+    pub relics: Vec<Relic>,
+    pub shop: Shop,
+    pub reel_editor: Option<ReelEditor>,
     pub luts: LookUpTables,
     pub settings: Settings,
     pub mouse: MouseContext,
@@ -35,13 +62,27 @@ pub struct Context {
 
 impl Default for Context {
     fn default() -> Self {
+        let seed: u64 = random_seed();
+
         Self {
             score: 0,
             coins: 600,
             luck: 0,
+            luck_armed: false,
+            dry_spin_streak: 0,
             game_time: 0.0,
+            seed,
+            rng: build_rng(seed),
             impulse_timestamps: HashMap::new(),
             poker_hand: None,
+            hand_levels: HashMap::new(),
+            hand_play_counts: HashMap::new(),
+            paytable_visible: false,
+            resume_prompt_open: false,
+            save_disabled: false,
+            scoring: None,
+            hint: None,
+            spin_odds: None,
             table_card_slots: build_card_slots(
                 TABLE_ORIGIN_X,
                 TABLE_ORIGIN_Y,
@@ -81,14 +122,20 @@ impl Default for Context {
                 columns: vec![
                     SlotMachineColumn {
                         cursor: 0.0,
-                        cards: standard_52_deck(),
+                        cards: build_reel(standard_52_deck()),
                         spin_duration: 0.0,
                         spin_time_remaining: 0.0,
                         spin_speed: 0.0,
+                        lucky_ranks: vec![],
+                        luck_nudge: 0,
                     };
                     6
                 ],
             },
+            run: Run::default(),
+            relics: vec![],
+            shop: Shop::default(),
+            reel_editor: None,
             resize_update_accumulator: 0.0,
             fps_counter: FPSCounter::new(0.08),
         }
@@ -115,4 +162,8 @@ pub struct LookUpTables {
 #[derive(Hash, Eq, PartialEq)]
 pub enum ImpulseId {
     NoSpaceInHandHint,
+    GameSaved,
+    SaveFailed,
+    ScreenshotSaved,
+    ScreenshotFailed,
 }
@@ -1,4 +1,4 @@
-use crate::renderer::{DrawCall, RichText};
+use crate::renderer::{DrawCall, Layer, RichText};
 
 pub struct FPSCounter {
     fps_ema: f32,
@@ -34,5 +34,6 @@ pub fn draw_fps_counter(draw_queue: &mut Vec<DrawCall>, x: u16, y: u16, fps_coun
         x,
         y,
         rich_text: RichText::new(format!("FPS: {:2.0}", fps_counter.fps_ema)),
+        layer: Layer::Overlay,
     });
 }
@@ -0,0 +1,516 @@
+use crossterm::event::Event;
+use std::{cmp, io};
+
+use crate::{
+    TERM_SCREEN_HEIGHT, TERM_SCREEN_WIDTH,
+    backend::Backend,
+    button::{Button, ButtonId, draw_button},
+    card::Card,
+    card_ops::{CardDragState, draw_dragged_card},
+    constants::SIDEBAR_BORDER_X,
+    context::{Context, ImpulseId},
+    fps_counter::draw_fps_counter,
+    hand::{HAND_ORIGIN_X, HAND_ORIGIN_Y, draw_hand, draw_hand_card_slots},
+    hint::draw_hint,
+    input::{ProgramStatus, resolve_input},
+    luck::{
+        LUCK_COLOR, LUCKY_SPIN_COST, apply_lucky_spin, award_dry_spin_luck, can_arm_lucky_spin,
+        draw_luck_nudge_markers,
+    },
+    odds::{draw_spin_odds, update_spin_odds},
+    paytable::{draw_paytable, paytable_covers},
+    poker_hand::{hand_level, hand_payout, update_current_poker_hand},
+    reel_editor::{draw_reel_editor, push_reel_editor_buttons},
+    relic::{draw_relic_tooltip, draw_relics, on_burn_relics, on_spin_relics},
+    renderer::{
+        CellRun, DrawCall, Hsl, Rgba, RichText, compose_buffer, diff_buffers, draw_rect, draw_text,
+        fill_screen_background,
+    },
+    rng::draw_seed,
+    run::{
+        RUN_OVER_PANEL_HEIGHT, RUN_OVER_PANEL_WIDTH, RUN_OVER_PANEL_X, RUN_OVER_PANEL_Y, RunPhase,
+        can_afford_spin, draw_round_info, draw_run_over_panel, run_is_active, start_new_run,
+        update_run,
+    },
+    save::{draw_resume_prompt, draw_save_notice, push_resume_prompt_buttons},
+    scoring::{draw_score_breakdown, scoring_in_progress, start_scoring, update_scoring},
+    shader::{apply_gamma, apply_vignette, draw_bg_shader},
+    shop::{draw_shop, push_shop_buttons},
+    slot_machine::{
+        SLOTS_COLUMNS_X_SPACING, SLOTS_NEIGHBOR_ROW_COUNT, SLOTS_ORIGIN_X, SLOTS_ORIGIN_Y,
+        SlotMachineState, calc_column_spin_duration_sec, draw_slots, draw_slots_column_shadows,
+        draw_slots_panel, get_column_card, slots_are_spinning,
+        slots_center_row_indexes_matching_card, spin_cost, spin_slots_column,
+    },
+    table::{TABLE_ORIGIN_X, TABLE_ORIGIN_Y, draw_table, draw_table_card_slots},
+    utils::center_text_unicode,
+};
+
+/// Every button the player can currently see, built fresh each tick.
+pub fn build_buttons(ctx: &Context) -> Vec<Button> {
+    let mut buttons: Vec<Button> = vec![];
+
+    // Spin button
+    buttons.push(Button {
+        id: ButtonId::Spin,
+        x: SIDEBAR_BORDER_X + 3,
+        y: 9,
+        w: 12,
+        h: 1,
+        text: format!(
+            "${cost} SPIN",
+            cost = spin_cost(ctx.slot_machine.spin_count),
+        ),
+        color: Rgba::from_u8(255, 210, 140, 1.0),
+        on_click: Box::new(move |ctx| {
+            apply_lucky_spin(ctx);
+
+            for (column_index, column) in ctx.slot_machine.columns.iter_mut().enumerate() {
+                let spin_duration: f32 = calc_column_spin_duration_sec(column_index);
+                column.spin_duration = spin_duration;
+                column.spin_time_remaining = spin_duration;
+            }
+
+            ctx.slot_machine.state = SlotMachineState::Spinning;
+            ctx.coins -= spin_cost(ctx.slot_machine.spin_count);
+            ctx.slot_machine.spin_count += 1;
+            on_spin_relics(ctx);
+        }),
+        enabled_when: Box::new(|ctx| {
+            run_is_active(ctx)
+                && matches!(ctx.slot_machine.state, SlotMachineState::Idle)
+                && can_afford_spin(ctx)
+                && !scoring_in_progress(ctx)
+        }),
+        allow_rmb: false,
+    });
+
+    // Lucky spin toggle
+    buttons.push(Button {
+        id: ButtonId::Luck,
+        x: SIDEBAR_BORDER_X + 3,
+        y: 12,
+        w: 12,
+        h: 1,
+        text: if ctx.luck_armed {
+            format!("#{LUCKY_SPIN_COST} LUCK ON")
+        } else {
+            format!("#{LUCKY_SPIN_COST} LUCK")
+        },
+        color: if ctx.luck_armed {
+            LUCK_COLOR
+        } else {
+            Rgba::from_u8(110, 170, 110, 1.0)
+        },
+        on_click: Box::new(move |ctx: &mut Context| {
+            ctx.luck_armed = !ctx.luck_armed;
+        }),
+        enabled_when: Box::new(|ctx| {
+            run_is_active(ctx)
+                && matches!(ctx.slot_machine.state, SlotMachineState::Idle)
+                && (ctx.luck_armed || can_arm_lucky_spin(ctx))
+        }),
+        allow_rmb: false,
+    });
+
+    // Play button
+    buttons.push(Button {
+        id: ButtonId::Play,
+        x: SIDEBAR_BORDER_X + 3,
+        y: 14,
+        w: 12,
+        h: 1,
+        text: "PLAY".to_string(),
+        color: Rgba::from_u8(160, 210, 140, 1.0),
+        on_click: Box::new(start_scoring),
+        enabled_when: Box::new(|ctx| {
+            let any_cards_on_table: bool =
+                ctx.table_card_slots.iter().any(|slot| slot.card.is_some());
+            run_is_active(ctx) && any_cards_on_table && !scoring_in_progress(ctx)
+        }),
+        allow_rmb: false,
+    });
+
+    // Burn button
+    buttons.push(Button {
+        id: ButtonId::Burn,
+        x: SIDEBAR_BORDER_X + 3,
+        y: 16,
+        w: 12,
+        h: 1,
+        text: "BURN".to_string(),
+        color: Rgba::from_u8(255, 120, 80, 1.0),
+        on_click: Box::new(move |ctx: &mut Context| {
+            let mut burned_card_count: i32 = 0;
+            ctx.table_card_slots.iter_mut().for_each(|slot| {
+                if slot.card.take().is_some() {
+                    burned_card_count += 1;
+                }
+            });
+
+            // Every burned card is turned into luck
+            ctx.luck += burned_card_count;
+            on_burn_relics(ctx, burned_card_count);
+            update_current_poker_hand(ctx);
+        }),
+        enabled_when: Box::new(|ctx| {
+            let any_cards_on_table: bool =
+                ctx.table_card_slots.iter().any(|slot| slot.card.is_some());
+            run_is_active(ctx) && any_cards_on_table && !scoring_in_progress(ctx)
+        }),
+        allow_rmb: false,
+    });
+
+    // Slots post-spin reward buttons
+    // let cards_in_hand_count: usize = ctx
+    //     .hand_card_slots
+    //     .iter()
+    //     .filter(|slot| slot.card.is_some())
+    //     .count();
+
+    // let cards_on_table_count: usize = ctx
+    //     .table_card_slots
+    //     .iter()
+    //     .filter(|slot| slot.card.is_some())
+    //     .count();
+
+    // let at_least_one_empty_slot_in_hand: bool = cards_in_hand_count < HAND_SLOT_COUNT.into();
+    // let no_cards_on_table: bool = cards_on_table_count == 0;
+
+    if run_is_active(ctx) && matches!(ctx.slot_machine.state, SlotMachineState::PostSpin) {
+        for column_index in 0..ctx.slot_machine.columns.len() {
+            let index: usize = column_index;
+            buttons.push(Button {
+                id: ButtonId::TakeColumn { index },
+                x: SLOTS_ORIGIN_X + column_index as u16 * SLOTS_COLUMNS_X_SPACING,
+                y: SLOTS_ORIGIN_Y - SLOTS_NEIGHBOR_ROW_COUNT as u16,
+                w: 3,
+                h: 1 + SLOTS_NEIGHBOR_ROW_COUNT as u16 * 2,
+                text: "".to_string(),
+                color: Rgba::from_u8(0, 0, 0, 0.0),
+                on_click: Box::new(move |ctx: &mut Context| {
+                    let empty_hand_slot_count = ctx
+                        .hand_card_slots
+                        .iter()
+                        .filter(|slot| slot.card.is_none())
+                        .count();
+
+                    if empty_hand_slot_count == 0 {
+                        ctx.impulse_timestamps
+                            .insert(ImpulseId::NoSpaceInHandHint, ctx.game_time);
+                        return;
+                    }
+
+                    ctx.slot_machine.state = SlotMachineState::Idle;
+
+                    let clicked_card: Card = *get_column_card(0, &ctx.slot_machine.columns[index]);
+
+                    // Get all matching indexes
+                    let all_matching_indexes =
+                        slots_center_row_indexes_matching_card(&clicked_card, ctx);
+
+                    // Calculate how many cards we can actually take
+                    let max_cards_to_take = empty_hand_slot_count.min(all_matching_indexes.len());
+
+                    // Take the clicked card and then other matching cards
+                    let mut cards_to_take = Vec::new();
+
+                    // Always take the clicked card if we can take at least 1
+                    cards_to_take.push(clicked_card);
+
+                    // Then take up to (max_cards_to_take - 1) other matching cards
+                    let other_matching_cards: Vec<Card> = all_matching_indexes
+                        .iter()
+                        .filter(|&&col_idx| col_idx != index)
+                        .take(max_cards_to_take.saturating_sub(1))
+                        .map(|&col_idx| *get_column_card(0, &ctx.slot_machine.columns[col_idx]))
+                        .collect();
+
+                    cards_to_take.extend(other_matching_cards);
+
+                    // Put cards in hand
+                    for card in cards_to_take {
+                        if let Some(empty_slot) = ctx
+                            .hand_card_slots
+                            .iter_mut()
+                            .find(|slot| slot.card.is_none())
+                        {
+                            empty_slot.card = Some(card);
+                        }
+                    }
+                }),
+                enabled_when: Box::new(|_| true),
+                allow_rmb: true,
+            });
+        }
+    }
+
+    push_shop_buttons(&mut buttons, ctx);
+    push_reel_editor_buttons(&mut buttons, ctx);
+
+    // New run button, only present once the run is over
+    if matches!(ctx.run.phase, RunPhase::GameOver | RunPhase::Victory) {
+        buttons.push(Button {
+            id: ButtonId::NewRun,
+            x: RUN_OVER_PANEL_X + (RUN_OVER_PANEL_WIDTH - 12) / 2,
+            y: RUN_OVER_PANEL_Y + RUN_OVER_PANEL_HEIGHT - 2,
+            w: 12,
+            h: 1,
+            text: "NEW RUN".to_string(),
+            color: Rgba::from_u8(255, 210, 140, 1.0),
+            on_click: Box::new(start_new_run),
+            enabled_when: Box::new(|_| true),
+            allow_rmb: false,
+        });
+    }
+
+    // The paytable overlay hides everything underneath it
+    buttons.retain(|button| !paytable_covers(ctx, button.x, button.y));
+
+    // Nothing but the resume prompt can be used until it's answered
+    if ctx.resume_prompt_open {
+        buttons.clear();
+        push_resume_prompt_buttons(&mut buttons, ctx);
+    }
+
+    buttons
+}
+
+/// Advances everything that moves on its own: spinning reels, scoring and the run itself.
+pub fn update_game(ctx: &mut Context, dt: f32) {
+    if matches!(ctx.slot_machine.state, SlotMachineState::Spinning) {
+        for column in &mut ctx.slot_machine.columns {
+            const MAX_SPIN_SPEED: f32 = 60.0;
+            spin_slots_column(column, dt, MAX_SPIN_SPEED);
+        }
+
+        if slots_are_spinning(&ctx.slot_machine) {
+            ctx.slot_machine.state = SlotMachineState::PostSpin;
+            award_dry_spin_luck(ctx);
+        }
+    }
+
+    update_scoring(ctx, dt);
+    update_run(ctx);
+}
+
+/// One frame of a session: input, game logic and drawing the changed cells to `backend`.
+pub fn tick(
+    ctx: &mut Context,
+    dt: f32,
+    events: Vec<Event>,
+    backend: &mut impl Backend,
+) -> io::Result<ProgramStatus> {
+    let buttons: Vec<Button> = build_buttons(ctx);
+
+    // --- Inputs ---
+    for event in events {
+        if let ProgramStatus::Exit = resolve_input(ctx, event, &buttons) {
+            return Ok(ProgramStatus::Exit);
+        }
+    }
+
+    update_game(ctx, dt);
+    update_spin_odds(ctx, &buttons);
+
+    // --- Rendering ---
+    fill_screen_background(&mut ctx.screen.new_buffer, (5, 37, 5));
+    // Calls stack in push order within a layer, tooltips, the dragged card and the FPS
+    // counter sit on layers of their own
+    let mut draw_queue: Vec<DrawCall> = vec![];
+    draw_fps_counter(&mut draw_queue, 0, 0, &ctx.fps_counter);
+
+    // Sidebar
+    draw_rect(
+        &mut draw_queue,
+        SIDEBAR_BORDER_X as i16,
+        0,
+        17,
+        TERM_SCREEN_HEIGHT,
+        Rgba::from_u8(37, 16, 16, 1.0),
+    );
+
+    if ctx.settings.bg_shader_enabled {
+        // Above slots strip
+        draw_bg_shader(&mut draw_queue, 0, 0, SIDEBAR_BORDER_X, 1, ctx.game_time);
+
+        // Main play area
+        let y: u16 = 10;
+        draw_bg_shader(
+            &mut draw_queue,
+            0,
+            y,
+            SIDEBAR_BORDER_X,
+            TERM_SCREEN_HEIGHT - y,
+            ctx.game_time,
+        );
+    }
+
+    draw_slots_panel(&mut draw_queue, 0, 1, 37, 7);
+    draw_slots(&mut draw_queue, SLOTS_ORIGIN_X, SLOTS_ORIGIN_Y, ctx);
+    draw_slots_column_shadows(&mut draw_queue, SLOTS_ORIGIN_X, SLOTS_ORIGIN_Y);
+    draw_luck_nudge_markers(&mut draw_queue, SLOTS_ORIGIN_X, SLOTS_ORIGIN_Y, ctx);
+
+    draw_table_card_slots(&mut draw_queue, TABLE_ORIGIN_X, TABLE_ORIGIN_Y, ctx);
+    draw_table(&mut draw_queue, ctx);
+
+    draw_hand_card_slots(
+        &mut draw_queue,
+        HAND_ORIGIN_X,
+        HAND_ORIGIN_Y,
+        ctx.hand_card_slots.len(),
+    );
+    draw_hand(&mut draw_queue, ctx);
+
+    draw_sidebar_border(&mut draw_queue, SIDEBAR_BORDER_X);
+
+    draw_round_info(&mut draw_queue, ctx);
+
+    // Score drawing
+    draw_text(
+        &mut draw_queue,
+        SIDEBAR_BORDER_X + 3,
+        3,
+        RichText::new(format!("{:>12}", ctx.score))
+            .with_fg(Rgba::from_u8(190, 230, 255, 1.0))
+            .with_bold(true),
+    );
+
+    // Used for aligning the currency symbols of all currency displays
+    let coin_display_width: u16 = format!("{}", ctx.coins).chars().count() as u16;
+    let luck_display_width: u16 = format!("{}", ctx.luck).chars().count() as u16;
+    let currency_width: u16 = cmp::max(coin_display_width, luck_display_width);
+
+    // Coin currency drawing
+    let coin_formatted: String =
+        format!("$ {:>width$}", ctx.coins, width = currency_width as usize);
+    let coin_amount_rich_text = RichText::new(format!("{coin_formatted:>12}"))
+        .with_fg(Rgba::from_u8(255, 255, 155, 1.0))
+        .with_bold(true);
+    draw_text(
+        &mut draw_queue,
+        SIDEBAR_BORDER_X + 3,
+        5,
+        coin_amount_rich_text,
+    );
+
+    // Luck currency drawing
+    let luck_formatted: String = format!("# {:>width$}", ctx.luck, width = currency_width as usize);
+    let luck_amount_rich_text = RichText::new(format!("{:>12}", luck_formatted))
+        .with_fg(LUCK_COLOR)
+        .with_bold(true);
+    draw_text(
+        &mut draw_queue,
+        SIDEBAR_BORDER_X + 3,
+        6,
+        luck_amount_rich_text,
+    );
+
+    draw_relics(&mut draw_queue, ctx);
+    draw_relic_tooltip(&mut draw_queue, ctx);
+
+    // Poker hand preview, replaced by the score breakdown while a hand is scoring
+    if !scoring_in_progress(ctx)
+        && let Some(poker_hand) = ctx.poker_hand
+    {
+        let bonus_coins: i32 = hand_payout(ctx, poker_hand);
+        let text_centered: String = if bonus_coins == 0 {
+            center_text_unicode(poker_hand.repr().to_string(), SIDEBAR_BORDER_X as usize)
+        } else {
+            center_text_unicode(
+                format!(
+                    "{poker_hand} Lv{level} (+{bonus_coins})",
+                    poker_hand = poker_hand.repr(),
+                    level = hand_level(ctx, poker_hand),
+                ),
+                SIDEBAR_BORDER_X as usize,
+            )
+        };
+
+        draw_text(
+            &mut draw_queue,
+            0,
+            18,
+            RichText::new(text_centered)
+                .with_bold(true)
+                .with_italic(true),
+        );
+    }
+
+    draw_hint(&mut draw_queue, ctx);
+    draw_score_breakdown(&mut draw_queue, ctx);
+    draw_shop(&mut draw_queue, ctx);
+    draw_reel_editor(&mut draw_queue, ctx);
+    draw_run_over_panel(&mut draw_queue, ctx);
+
+    for button in &buttons {
+        draw_button(&mut draw_queue, ctx, button)
+    }
+    draw_spin_odds(&mut draw_queue, ctx, &buttons);
+
+    draw_paytable(&mut draw_queue, ctx);
+
+    draw_resume_prompt(&mut draw_queue, ctx);
+    draw_save_notice(&mut draw_queue, ctx);
+    draw_seed(&mut draw_queue, ctx);
+
+    if let CardDragState::Dragging { card, .. } = ctx.mouse.card_drag.clone() {
+        draw_dragged_card(&mut draw_queue, &card, ctx);
+    }
+
+    // --- Renderer boilerplate ---
+    compose_buffer(&mut ctx.screen.new_buffer, &draw_queue);
+
+    // Post processing step
+    apply_gamma(&mut ctx.screen.new_buffer, &ctx.luts.gamma);
+    if ctx.settings.vignette_enabled {
+        apply_vignette(&mut ctx.screen.new_buffer, &ctx.luts.vignette);
+    }
+
+    let diff: Vec<CellRun> = diff_buffers(&ctx.screen.old_buffer, &ctx.screen.new_buffer);
+
+    for run in diff {
+        backend.draw_run(run.x, run.y, run.cells)?;
+    }
+
+    // This doesnt work on linux for some reason
+    ctx.resize_update_accumulator += dt;
+    if ctx.resize_update_accumulator >= 0.2 {
+        ctx.resize_update_accumulator = 0.0;
+        backend.resize(TERM_SCREEN_WIDTH, TERM_SCREEN_HEIGHT)?;
+    }
+
+    backend.flush()?;
+    ctx.screen.swap_buffers();
+    Ok(ProgramStatus::Running)
+}
+
+fn draw_sidebar_border(draw_queue: &mut Vec<DrawCall>, x: u16) {
+    let half_height: i16 = (TERM_SCREEN_HEIGHT / 2) as i16;
+
+    for y in 0..TERM_SCREEN_HEIGHT as i16 {
+        draw_rect(draw_queue, x as i16, y, 1, TERM_SCREEN_HEIGHT, {
+            let mut hsl: Hsl = Rgba::from_u8(176, 144, 61, 1.0).into();
+            let distance_from_center: i16 = (y - half_height + 1).abs();
+            hsl.l *= 0.6 + 0.045 * (half_height - distance_from_center) as f32;
+            hsl.s *= 0.8;
+            hsl.into()
+        });
+    }
+
+    // Shadow
+    let shadow_width: i32 = 2;
+    for i in 0..shadow_width {
+        let t: f32 = 1.0 - (i as f32 / shadow_width as f32);
+        let alpha: f32 = t * 0.1;
+
+        draw_rect(
+            draw_queue,
+            x as i16 - 1 - i as i16,
+            0,
+            1,
+            TERM_SCREEN_HEIGHT,
+            Rgba::from_f32(0.0, 0.0, 0.0, alpha),
+        );
+    }
+}
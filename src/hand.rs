@@ -1,15 +1,20 @@
 use crate::{
     card::{BIG_CARD_HEIGHT, BIG_CARD_WIDTH, draw_calls_playing_card_big},
     card_ops::{CardDragAndDropLocation, CardDragState},
+    card_slot::CardSlot,
     constants::CARD_SLOT_COLOR,
     context::{Context, ImpulseId},
+    hint::{HINT_COLOR, is_hinted},
     renderer::{DrawCall, Hsl, Rgba, draw_rect},
+    scoring::{CHIPS_COLOR, ScoreSource, is_scoring_source_active},
 };
 
 pub const HAND_ORIGIN_X: u16 = 5;
 pub const HAND_ORIGIN_Y: u16 = 20;
 pub const HAND_CARD_X_SPACING: u16 = 4;
 pub const HAND_SLOT_COUNT: u16 = 7;
+/// Hand slots past this would overlap the sidebar.
+pub const MAX_HAND_SLOT_COUNT: usize = 8;
 
 pub fn draw_hand(draw_queue: &mut Vec<DrawCall>, ctx: &Context) {
     let slots_with_cards = ctx
@@ -32,6 +37,8 @@ pub fn draw_hand(draw_queue: &mut Vec<DrawCall>, ctx: &Context) {
             continue;
         }
 
+        let is_scoring: bool = is_scoring_source_active(ctx, ScoreSource::Hand(index));
+        let is_hinted: bool = is_hinted(ctx, &CardDragAndDropLocation::Hand { index });
         let mut draw_calls: Vec<DrawCall> =
             draw_calls_playing_card_big(slot.x as i16, slot.y as i16, card);
 
@@ -46,6 +53,14 @@ pub fn draw_hand(draw_queue: &mut Vec<DrawCall>, ctx: &Context) {
             dc.rich_text.fg = fg_hsl.into();
             dc.rich_text.bg = bg_hsl.into();
 
+            if is_scoring {
+                dc.rich_text.bg = dc.rich_text.bg.lerp(CHIPS_COLOR, 0.5);
+            }
+
+            if is_hinted {
+                dc.rich_text.bg = dc.rich_text.bg.lerp(HINT_COLOR, 0.4);
+            }
+
             // Out of space hint impulse
             if let Some(timestamp) = ctx.impulse_timestamps.get(&ImpulseId::NoSpaceInHandHint) {
                 let duration: f32 = 0.75;
@@ -72,8 +87,8 @@ pub fn draw_hand(draw_queue: &mut Vec<DrawCall>, ctx: &Context) {
     }
 }
 
-pub fn draw_hand_card_slots(draw_queue: &mut Vec<DrawCall>, x: u16, y: u16) {
-    for n in 0..HAND_SLOT_COUNT {
+pub fn draw_hand_card_slots(draw_queue: &mut Vec<DrawCall>, x: u16, y: u16, slot_count: usize) {
+    for n in 0..slot_count as u16 {
         draw_rect(
             draw_queue,
             (x + n * HAND_CARD_X_SPACING) as i16,
@@ -84,3 +99,15 @@ pub fn draw_hand_card_slots(draw_queue: &mut Vec<DrawCall>, x: u16, y: u16) {
         );
     }
 }
+
+pub fn add_hand_slot(ctx: &mut Context) {
+    if ctx.hand_card_slots.len() >= MAX_HAND_SLOT_COUNT {
+        return;
+    }
+
+    ctx.hand_card_slots.push(CardSlot {
+        x: HAND_ORIGIN_X + ctx.hand_card_slots.len() as u16 * HAND_CARD_X_SPACING,
+        y: HAND_ORIGIN_Y,
+        card: None,
+    });
+}
@@ -0,0 +1,262 @@
+use std::collections::HashSet;
+
+use crate::{
+    card::Card,
+    card_ops::{CardDragAndDropLocation, CardDragState},
+    constants::SIDEBAR_BORDER_X,
+    context::Context,
+    poker_hand::{PokerHand, eval_poker_hand, update_current_poker_hand},
+    renderer::{DrawCall, Rgba, RichText, draw_text},
+    run::run_is_active,
+    scoring::{build_score_breakdown_for_layout, scoring_in_progress},
+    table::TABLE_SLOT_COUNT,
+    utils::center_text_unicode,
+};
+
+pub const HINT_COLOR: Rgba = Rgba {
+    r: 150,
+    g: 255,
+    b: 170,
+    a: 1.0,
+};
+
+/// The best scoring play among the cards in hand and on the table.
+pub struct Hint {
+    /// Where the cards to play sit, on the table or in hand.
+    pub locations: Vec<CardDragAndDropLocation>,
+    pub poker_hand: PokerHand,
+    /// Coins the play pays out.
+    pub reward: i32,
+    /// Table and hand cards at the time of the search, any change makes the hint stale.
+    searched_cards: Vec<Option<Card>>,
+}
+
+/// Every non-empty subset of `items` that fits on the table.
+pub fn table_sized_subsets<T: Clone>(items: &[T]) -> Vec<Vec<T>> {
+    let mut subsets: Vec<Vec<T>> = vec![vec![]];
+
+    for item in items {
+        let extended: Vec<Vec<T>> = subsets
+            .iter()
+            .filter(|subset| subset.len() < TABLE_SLOT_COUNT as usize)
+            .map(|subset| {
+                let mut subset: Vec<T> = subset.clone();
+                subset.push(item.clone());
+                subset
+            })
+            .collect();
+        subsets.extend(extended);
+    }
+
+    subsets.retain(|subset| !subset.is_empty());
+    subsets
+}
+
+/// Card of every table slot, then of every hand slot.
+type CardLayout = (Vec<Option<Card>>, Vec<Option<Card>>);
+
+fn all_slot_cards(ctx: &Context) -> Vec<Option<Card>> {
+    ctx.table_card_slots
+        .iter()
+        .chain(&ctx.hand_card_slots)
+        .map(|slot| slot.card)
+        .collect()
+}
+
+fn card_at(ctx: &Context, location: &CardDragAndDropLocation) -> Option<Card> {
+    match location {
+        CardDragAndDropLocation::Hand { index } => ctx.hand_card_slots[*index].card,
+        CardDragAndDropLocation::Table { index } => ctx.table_card_slots[*index].card,
+    }
+}
+
+/// Table and hand cards once the cards at `locations` are the only ones on the table.
+/// Hand cards keep their slots and table cards that aren't played move into free hand
+/// slots, `None` if those run out.
+fn layout_for_play(ctx: &Context, locations: &[CardDragAndDropLocation]) -> Option<CardLayout> {
+    let mut table_cards: Vec<Option<Card>> = locations
+        .iter()
+        .map(|location| card_at(ctx, location))
+        .collect();
+    table_cards.resize(ctx.table_card_slots.len(), None);
+
+    let mut hand_cards: Vec<Option<Card>> = ctx
+        .hand_card_slots
+        .iter()
+        .enumerate()
+        .map(|(index, slot)| {
+            let played: bool = locations.contains(&CardDragAndDropLocation::Hand { index });
+            if played { None } else { slot.card }
+        })
+        .collect();
+
+    let unplayed_table_cards = ctx
+        .table_card_slots
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| !locations.contains(&CardDragAndDropLocation::Table { index: *index }))
+        .filter_map(|(_, slot)| slot.card);
+
+    for card in unplayed_table_cards {
+        let empty_slot: &mut Option<Card> = hand_cards.iter_mut().find(|card| card.is_none())?;
+        *empty_slot = Some(card);
+    }
+
+    Some((table_cards, hand_cards))
+}
+
+/// Tries every subset of the cards in hand and on the table, the highest payout wins.
+/// Ties go to fewer cards, which keeps more in hand for the next play.
+///
+/// Equal cards pay the same wherever they sit, so each set of cards is only scored once.
+pub fn find_best_play(ctx: &Context) -> Option<Hint> {
+    let occupied_locations: Vec<CardDragAndDropLocation> = ctx
+        .table_card_slots
+        .iter()
+        .enumerate()
+        .filter(|(_, slot)| slot.card.is_some())
+        .map(|(index, _)| CardDragAndDropLocation::Table { index })
+        .chain(
+            ctx.hand_card_slots
+                .iter()
+                .enumerate()
+                .filter(|(_, slot)| slot.card.is_some())
+                .map(|(index, _)| CardDragAndDropLocation::Hand { index }),
+        )
+        .collect();
+
+    // Index of the first location holding an equal card
+    let card_classes: Vec<usize> = occupied_locations
+        .iter()
+        .map(|location| {
+            occupied_locations
+                .iter()
+                .position(|other| card_at(ctx, other) == card_at(ctx, location))
+                .expect("A location holds the same card as itself")
+        })
+        .collect();
+    let location_indexes: Vec<usize> = (0..occupied_locations.len()).collect();
+
+    let mut searched_classes: HashSet<Vec<usize>> = HashSet::new();
+    let mut best_hint: Option<Hint> = None;
+
+    for subset in table_sized_subsets(&location_indexes) {
+        let locations: Vec<CardDragAndDropLocation> = subset
+            .iter()
+            .map(|&index| occupied_locations[index].clone())
+            .collect();
+        let Some((table_cards, hand_cards)) = layout_for_play(ctx, &locations) else {
+            continue;
+        };
+
+        let mut classes: Vec<usize> = subset.iter().map(|&index| card_classes[index]).collect();
+        classes.sort_unstable();
+        if !searched_classes.insert(classes) {
+            continue;
+        }
+
+        let played_cards: Vec<&Card> = table_cards.iter().flatten().collect();
+        let (poker_hand, scoring_cards): (PokerHand, Vec<Card>) = eval_poker_hand(&played_cards);
+        let reward: i32 = build_score_breakdown_for_layout(
+            ctx,
+            poker_hand,
+            &scoring_cards,
+            &table_cards,
+            &hand_cards,
+        )
+        .payout();
+
+        let is_better: bool = best_hint.as_ref().is_none_or(|best| {
+            reward > best.reward
+                || (reward == best.reward && locations.len() < best.locations.len())
+        });
+
+        if is_better {
+            best_hint = Some(Hint {
+                locations,
+                poker_hand,
+                reward,
+                searched_cards: all_slot_cards(ctx),
+            });
+        }
+    }
+
+    best_hint
+}
+
+/// Searches for the best play and highlights it until any card moves. Does nothing while
+/// a card is dragged, the drop still has to find it where it was picked up.
+pub fn show_hint(ctx: &mut Context) {
+    let is_dragging: bool = !matches!(ctx.mouse.card_drag, CardDragState::NotDragging);
+    if !run_is_active(ctx) || scoring_in_progress(ctx) || is_dragging {
+        return;
+    }
+
+    ctx.hint = find_best_play(ctx);
+}
+
+/// Same as `show_hint`, but also lays the cards out for the play right away.
+pub fn apply_hint(ctx: &mut Context) {
+    if !matches!(ctx.mouse.card_drag, CardDragState::NotDragging) {
+        return;
+    }
+
+    show_hint(ctx);
+
+    let Some(hint) = ctx.hint.take() else {
+        return;
+    };
+    let Some((table_cards, hand_cards)) = layout_for_play(ctx, &hint.locations) else {
+        return;
+    };
+
+    for (slot, card) in ctx.table_card_slots.iter_mut().zip(table_cards) {
+        slot.card = card;
+    }
+    for (slot, card) in ctx.hand_card_slots.iter_mut().zip(hand_cards) {
+        slot.card = card;
+    }
+    update_current_poker_hand(ctx);
+
+    // Played cards are packed to the left of the table
+    ctx.hint = Some(Hint {
+        locations: (0..hint.locations.len())
+            .map(|index| CardDragAndDropLocation::Table { index })
+            .collect(),
+        searched_cards: all_slot_cards(ctx),
+        ..hint
+    });
+}
+
+/// The hint, as long as no card moved since it was found.
+pub fn current_hint(ctx: &Context) -> Option<&Hint> {
+    ctx.hint
+        .as_ref()
+        .filter(|hint| !scoring_in_progress(ctx) && hint.searched_cards == all_slot_cards(ctx))
+}
+
+pub fn is_hinted(ctx: &Context, location: &CardDragAndDropLocation) -> bool {
+    current_hint(ctx).is_some_and(|hint| hint.locations.contains(location))
+}
+
+/// The hinted hand and its payout, between the table and the poker hand preview.
+pub fn draw_hint(draw_queue: &mut Vec<DrawCall>, ctx: &Context) {
+    let Some(hint) = current_hint(ctx) else {
+        return;
+    };
+
+    let text: String = format!(
+        "HINT {poker_hand} +${reward}",
+        poker_hand = hint.poker_hand.repr(),
+        reward = hint.reward
+    );
+
+    draw_text(
+        draw_queue,
+        0,
+        17,
+        RichText::new(center_text_unicode(text, SIDEBAR_BORDER_X as usize))
+            .with_fg(HINT_COLOR)
+            .with_bold(true),
+    );
+}
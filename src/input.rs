@@ -11,9 +11,14 @@ use crate::{
         location_has_card, place_card_at, swap_cards,
     },
     context::Context,
-    hand::{HAND_CARD_X_SPACING, HAND_ORIGIN_X, HAND_ORIGIN_Y, HAND_SLOT_COUNT},
+    hand::{HAND_CARD_X_SPACING, HAND_ORIGIN_X, HAND_ORIGIN_Y},
+    hint::{apply_hint, show_hint},
     poker_hand::update_current_poker_hand,
     renderer::{Screen, point_in_rect},
+    run::run_is_active,
+    save::save_game,
+    scoring::scoring_in_progress,
+    screenshot::{ScreenshotFormat, save_screenshot},
     table::{TABLE_CARD_X_SPACING, TABLE_ORIGIN_X, TABLE_ORIGIN_Y, TABLE_SLOT_COUNT},
 };
 
@@ -23,30 +28,40 @@ pub enum ProgramStatus {
     Exit,
 }
 
+/// Whether `event` quits the game, checked on its own while a replay drives the input.
+pub fn is_exit_event(event: &Event) -> bool {
+    matches!(
+        event,
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('q'),
+            kind: KeyEventKind::Press,
+            ..
+        })
+    )
+}
+
 pub fn resolve_input(ctx: &mut Context, event: Event, buttons: &[Button]) -> ProgramStatus {
     match event {
         Event::Resize(_, _) => {
             // Recreate screen on resize to avoid graphical anomalies
             ctx.screen = Screen::new(TERM_SCREEN_WIDTH, TERM_SCREEN_HEIGHT, (0, 0, 0));
         }
+        _ if is_exit_event(&event) => return ProgramStatus::Exit,
         Event::Key(KeyEvent {
             code: key_code,
-            kind: key_kind,
+            kind: KeyEventKind::Press,
             ..
-        }) => {
-            if key_kind == KeyEventKind::Press {
-                match key_code {
-                    KeyCode::Char('q') => return ProgramStatus::Exit,
-                    KeyCode::Char('v') => {
-                        ctx.settings.vignette_enabled = !ctx.settings.vignette_enabled
-                    }
-                    KeyCode::Char('b') => {
-                        ctx.settings.bg_shader_enabled = !ctx.settings.bg_shader_enabled
-                    }
-                    _ => {}
-                }
-            }
-        }
+        }) => match key_code {
+            KeyCode::Char('v') => ctx.settings.vignette_enabled = !ctx.settings.vignette_enabled,
+            KeyCode::Char('b') => ctx.settings.bg_shader_enabled = !ctx.settings.bg_shader_enabled,
+            KeyCode::Char('p') => ctx.paytable_visible = !ctx.paytable_visible,
+            KeyCode::Char('s') => save_game(ctx),
+            KeyCode::Char('h') => show_hint(ctx),
+            KeyCode::Char('H') => apply_hint(ctx),
+            KeyCode::Char('x') => save_screenshot(ctx, ScreenshotFormat::Html),
+            KeyCode::Char('X') => save_screenshot(ctx, ScreenshotFormat::Svg),
+            _ => {}
+        },
         Event::Mouse(mouse_event) => match mouse_event.kind {
             MouseEventKind::Down(MouseButton::Left) => on_left_click_down(ctx),
             MouseEventKind::Down(MouseButton::Right) => on_right_click_down(ctx, buttons),
@@ -79,6 +94,11 @@ pub fn drain_input() -> impl Iterator<Item = Event> {
 fn on_left_click_down(ctx: &mut Context) {
     ctx.mouse.is_left_down = true;
 
+    // Cards stay put while a played hand is being scored
+    if !run_is_active(ctx) || scoring_in_progress(ctx) {
+        return;
+    }
+
     // Drag detection
     // > Table
     let table_slots_with_cards = ctx
@@ -177,6 +197,11 @@ fn on_right_click_down(ctx: &mut Context, buttons: &[Button]) {
         (button.on_click)(ctx);
     }
 
+    // Cards stay put while a played hand is being scored
+    if !run_is_active(ctx) || scoring_in_progress(ctx) {
+        return;
+    }
+
     // Check if clicked on a table card first (Table -> Hand)
     for table_slot_index in 0..TABLE_SLOT_COUNT {
         let x1: u16 = TABLE_ORIGIN_X + table_slot_index * HAND_CARD_X_SPACING;
@@ -228,7 +253,7 @@ fn on_right_click_down(ctx: &mut Context, buttons: &[Button]) {
     }
 
     // Check if clicked on a hand card (Hand -> Table)
-    for hand_slot_index in 0..HAND_SLOT_COUNT {
+    for hand_slot_index in 0..ctx.hand_card_slots.len() as u16 {
         let x1: u16 = HAND_ORIGIN_X + hand_slot_index * HAND_CARD_X_SPACING;
         let y1: u16 = HAND_ORIGIN_Y;
 
@@ -0,0 +1,41 @@
+//! Game state, logic and rendering of term-slots, the binary only adds a terminal and a loop.
+
+pub mod backend;
+pub mod button;
+pub mod card;
+pub mod card_ops;
+pub mod card_slot;
+pub mod cast;
+pub mod color_depth;
+pub mod constants;
+pub mod context;
+pub mod fps_counter;
+pub mod fps_limiter;
+pub mod game;
+pub mod hand;
+pub mod hint;
+pub mod input;
+pub mod luck;
+pub mod odds;
+pub mod paytable;
+pub mod poker_hand;
+pub mod reel_editor;
+pub mod relic;
+pub mod renderer;
+pub mod replay;
+pub mod rng;
+pub mod run;
+pub mod save;
+pub mod scoring;
+pub mod screenshot;
+pub mod shader;
+pub mod shop;
+pub mod simulate;
+pub mod slot_machine;
+pub mod snapshot;
+pub mod strategy;
+pub mod table;
+pub mod utils;
+
+pub const TERM_SCREEN_WIDTH: u16 = 54;
+pub const TERM_SCREEN_HEIGHT: u16 = 30;
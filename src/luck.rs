@@ -0,0 +1,138 @@
+use rand::Rng;
+
+use crate::{
+    card::{Card, Rank},
+    context::Context,
+    renderer::{DrawCall, Layer, Rgba, RichText},
+    slot_machine::{
+        SLOTS_COLUMNS_X_SPACING, SLOTS_NEIGHBOR_ROW_COUNT, SlotMachineColumn, SlotMachineState,
+        get_column_card,
+    },
+};
+
+/// Luck spent on a single lucky spin.
+pub const LUCKY_SPIN_COST: i32 = 3;
+/// Chance of each column getting nudged towards a held rank during a lucky spin.
+pub const LUCKY_NUDGE_CHANCE: f64 = 0.5;
+
+pub const LUCK_COLOR: Rgba = Rgba {
+    r: 150,
+    g: 255,
+    b: 150,
+    a: 1.0,
+};
+
+/// Ranks a lucky spin aims for, which are the ranks of every held card. The rank of a
+/// wild rank card is only a placeholder, so those don't count.
+pub fn lucky_ranks(ctx: &Context) -> Vec<Rank> {
+    let mut ranks: Vec<Rank> = ctx
+        .hand_card_slots
+        .iter()
+        .chain(ctx.table_card_slots.iter())
+        .filter_map(|slot| slot.card.as_ref())
+        .filter(|card| !card.has_wild_rank())
+        .map(|card| card.rank)
+        .collect();
+    ranks.sort();
+    ranks.dedup();
+    ranks
+}
+
+pub fn can_arm_lucky_spin(ctx: &Context) -> bool {
+    ctx.luck >= LUCKY_SPIN_COST && !lucky_ranks(ctx).is_empty()
+}
+
+/// Spends luck on the spin that is about to start if a lucky spin is armed,
+/// rolling which columns will get nudged once they stop.
+pub fn apply_lucky_spin(ctx: &mut Context) {
+    for column in ctx.slot_machine.columns.iter_mut() {
+        column.luck_nudge = 0;
+        column.lucky_ranks.clear();
+    }
+
+    if !ctx.luck_armed || !can_arm_lucky_spin(ctx) {
+        ctx.luck_armed = false;
+        return;
+    }
+
+    let ranks: Vec<Rank> = lucky_ranks(ctx);
+    ctx.luck -= LUCKY_SPIN_COST;
+    ctx.luck_armed = false;
+
+    for column in ctx.slot_machine.columns.iter_mut() {
+        if ctx.rng.random_bool(LUCKY_NUDGE_CHANCE) {
+            column.lucky_ranks = ranks.clone();
+        }
+    }
+}
+
+/// Nudges a freshly stopped column to the closest visible card of a lucky rank.
+pub fn nudge_column_towards_lucky_ranks(column: &mut SlotMachineColumn) {
+    let ranks: Vec<Rank> = std::mem::take(&mut column.lucky_ranks);
+    if ranks.is_empty() {
+        return;
+    }
+
+    // Closest rows first, so the nudge stays as small as possible
+    let mut row_offsets: Vec<i16> =
+        (-SLOTS_NEIGHBOR_ROW_COUNT..=SLOTS_NEIGHBOR_ROW_COUNT).collect();
+    row_offsets.sort_by_key(|offset| offset.abs());
+
+    let maybe_offset: Option<i16> = row_offsets.into_iter().find(|&row_offset| {
+        let card: &Card = get_column_card(row_offset, column);
+        ranks.contains(&card.rank)
+    });
+
+    // Moving the cursor itself keeps the next spin starting from the nudged stop
+    if let Some(row_offset) = maybe_offset {
+        column.cursor = (column.cursor as i16 + row_offset) as f32;
+        column.luck_nudge = row_offset;
+    }
+}
+
+/// Luck income for spins that didn't show a single matching rank in the center row.
+/// Consecutive dry spins pay out increasingly more.
+pub fn award_dry_spin_luck(ctx: &mut Context) {
+    let center_ranks: Vec<Rank> = ctx
+        .slot_machine
+        .columns
+        .iter()
+        .map(|column| get_column_card(0, column).rank)
+        .collect();
+
+    let any_ranks_match: bool = center_ranks
+        .iter()
+        .enumerate()
+        .any(|(index, rank)| center_ranks[index + 1..].contains(rank));
+
+    if any_ranks_match {
+        ctx.dry_spin_streak = 0;
+    } else {
+        ctx.dry_spin_streak += 1;
+        ctx.luck += ctx.dry_spin_streak;
+    }
+}
+
+/// Marks the columns luck has nudged on the bottom border of the slots panel.
+pub fn draw_luck_nudge_markers(draw_queue: &mut Vec<DrawCall>, x: u16, y: u16, ctx: &Context) {
+    if !matches!(ctx.slot_machine.state, SlotMachineState::PostSpin) {
+        return;
+    }
+
+    for (column_index, column) in ctx.slot_machine.columns.iter().enumerate() {
+        if column.luck_nudge == 0 {
+            continue;
+        }
+
+        let arrow: &str = if column.luck_nudge > 0 { "▲" } else { "▼" };
+
+        draw_queue.push(DrawCall {
+            x: x + column_index as u16 * SLOTS_COLUMNS_X_SPACING,
+            y: y + SLOTS_NEIGHBOR_ROW_COUNT as u16 + 1,
+            rich_text: RichText::new(format!("#{arrow}"))
+                .with_fg(LUCK_COLOR)
+                .with_bold(true),
+            layer: Layer::default(),
+        });
+    }
+}
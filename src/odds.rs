@@ -0,0 +1,248 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+
+use rand::seq::IndexedRandom;
+
+use crate::{
+    button::{Button, ButtonId, get_button_at},
+    card::Card,
+    constants::SIDEBAR_BORDER_X,
+    context::Context,
+    hint::table_sized_subsets,
+    poker_hand::{PokerHand, eval_poker_hand},
+    renderer::{DrawCall, Layer, Rgba, RichText, draw_in_layer, draw_rect, draw_text},
+    rng::{GameRng, build_rng},
+    scoring::build_score_breakdown_for_layout,
+    slot_machine::ReelCard,
+};
+
+/// Simulated spins behind the odds, enough to keep them steady to about a percent.
+pub const SPIN_ODDS_SAMPLE_COUNT: usize = 4000;
+/// Simulated spins per frame, the odds fill in over many frames instead of stalling one.
+/// Every spin scores each play the center row allows, so a frame only fits a few dozen.
+pub const SPIN_ODDS_SAMPLES_PER_TICK: usize = 40;
+
+const ODDS_PANEL_WIDTH: u16 = 24;
+const ODDS_PANEL_X: u16 = SIDEBAR_BORDER_X - ODDS_PANEL_WIDTH - 1;
+const ODDS_PANEL_Y: u16 = 9;
+
+/// Chances of the next spin's center row, estimated by sampling every reel.
+pub struct SpinOdds {
+    /// Sampled spins that made each poker hand.
+    hand_counts: Vec<usize>,
+    /// Payout of all sampled spins together.
+    total_coins: i64,
+    pub sample_count: usize,
+    /// Seeded by the key, so the same setup always shows the same numbers
+    /// without touching the game's rng.
+    rng: GameRng,
+    /// Reels, relics, hand levels and hand cards the odds were estimated for.
+    key: u64,
+}
+
+impl SpinOdds {
+    fn new(key: u64) -> Self {
+        Self {
+            hand_counts: vec![0; PokerHand::iter().len()],
+            total_coins: 0,
+            sample_count: 0,
+            rng: build_rng(key),
+            key,
+        }
+    }
+
+    /// Chance of the center row making each poker hand or a better one.
+    pub fn at_least(&self) -> Vec<(PokerHand, f32)> {
+        // Walking down from the best hand accumulates the "or better" counts
+        let mut at_least: Vec<(PokerHand, f32)> = vec![];
+        let mut better_count: usize = 0;
+        for poker_hand in PokerHand::iter().rev() {
+            better_count += self.hand_counts[poker_hand as usize];
+            at_least.push((
+                poker_hand,
+                better_count as f32 / self.sample_count.max(1) as f32,
+            ));
+        }
+        at_least.reverse();
+        at_least
+    }
+
+    /// Average payout of the best play the center row allows, at most a table's worth of cards.
+    pub fn expected_coins(&self) -> f32 {
+        self.total_coins as f32 / self.sample_count.max(1) as f32
+    }
+}
+
+fn spin_odds_key(ctx: &Context) -> u64 {
+    let mut hasher: DefaultHasher = DefaultHasher::new();
+
+    for column in &ctx.slot_machine.columns {
+        for reel_card in &column.cards {
+            reel_card.card.hash(&mut hasher);
+            reel_card.weight.hash(&mut hasher);
+        }
+    }
+    ctx.relics.hash(&mut hasher);
+
+    let mut hand_levels: Vec<(PokerHand, i32)> = ctx
+        .hand_levels
+        .iter()
+        .map(|(poker_hand, level)| (*poker_hand, *level))
+        .collect();
+    hand_levels.sort();
+    hand_levels.hash(&mut hasher);
+
+    // Enhanced cards held in hand pay out too
+    for slot in &ctx.hand_card_slots {
+        slot.card.hash(&mut hasher);
+    }
+
+    hasher.finish()
+}
+
+/// Adds up to `sample_count` simulated spins to `odds`, stopping at `SPIN_ODDS_SAMPLE_COUNT`.
+/// Each column stops on a card with odds proportional to its weight on the reel strip.
+/// Luck nudges aren't accounted for.
+pub fn sample_spin_odds(ctx: &Context, odds: &mut SpinOdds, sample_count: usize) {
+    let hand_cards: Vec<Option<Card>> = ctx.hand_card_slots.iter().map(|slot| slot.card).collect();
+    let sample_count: usize = sample_count.min(SPIN_ODDS_SAMPLE_COUNT - odds.sample_count);
+
+    for _ in 0..sample_count {
+        let center_row: Vec<Card> = ctx
+            .slot_machine
+            .columns
+            .iter()
+            .map(|column| {
+                column
+                    .cards
+                    .choose_weighted(&mut odds.rng, |reel_card: &ReelCard| {
+                        reel_card.weight as u32
+                    })
+                    .expect("Reels are never empty")
+                    .card
+            })
+            .collect();
+
+        let cards: Vec<&Card> = center_row.iter().collect();
+        let (poker_hand, _): (PokerHand, Vec<Card>) = eval_poker_hand(&cards);
+
+        odds.hand_counts[poker_hand as usize] += 1;
+        odds.total_coins += best_table_payout(ctx, &center_row, &hand_cards) as i64;
+        odds.sample_count += 1;
+    }
+}
+
+/// Payout of the best play among `cards` that fits on the table, with `hand_cards` held.
+fn best_table_payout(ctx: &Context, cards: &[Card], hand_cards: &[Option<Card>]) -> i32 {
+    table_sized_subsets(cards)
+        .iter()
+        .map(|subset| {
+            let played_cards: Vec<&Card> = subset.iter().collect();
+            let (poker_hand, scoring_cards): (PokerHand, Vec<Card>) =
+                eval_poker_hand(&played_cards);
+            let table_cards: Vec<Option<Card>> = subset.iter().copied().map(Some).collect();
+
+            build_score_breakdown_for_layout(
+                ctx,
+                poker_hand,
+                &scoring_cards,
+                &table_cards,
+                hand_cards,
+            )
+            .payout()
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+fn spin_button_hovered(ctx: &Context, buttons: &[Button]) -> bool {
+    get_button_at(buttons, ctx.mouse.x, ctx.mouse.y)
+        .is_some_and(|button| button.id == ButtonId::Spin)
+}
+
+/// Estimates the odds while the SPIN button is hovered, a few samples per frame and
+/// only once per reel setup.
+pub fn update_spin_odds(ctx: &mut Context, buttons: &[Button]) {
+    if !spin_button_hovered(ctx, buttons) {
+        return;
+    }
+
+    let key: u64 = spin_odds_key(ctx);
+    let mut odds: SpinOdds = match ctx.spin_odds.take() {
+        Some(odds) if odds.key == key => odds,
+        _ => SpinOdds::new(key),
+    };
+    sample_spin_odds(ctx, &mut odds, SPIN_ODDS_SAMPLES_PER_TICK);
+    ctx.spin_odds = Some(odds);
+}
+
+/// Tooltip left of the SPIN button with the chance of every hand and the expected payout.
+pub fn draw_spin_odds(draw_queue: &mut Vec<DrawCall>, ctx: &Context, buttons: &[Button]) {
+    if !spin_button_hovered(ctx, buttons) {
+        return;
+    }
+    let Some(odds) = ctx.spin_odds.as_ref() else {
+        return;
+    };
+
+    draw_in_layer(draw_queue, Layer::Tooltip, |draw_queue| {
+        // Hands no sampled spin made are left out, which hides the rarest ones too
+        let rows: Vec<(PokerHand, f32)> = odds
+            .at_least()
+            .into_iter()
+            .filter(|(poker_hand, chance)| *poker_hand != PokerHand::HighCard && *chance > 0.0)
+            .collect();
+
+        draw_rect(
+            draw_queue,
+            ODDS_PANEL_X as i16,
+            ODDS_PANEL_Y as i16,
+            ODDS_PANEL_WIDTH,
+            rows.len() as u16 + 5,
+            Rgba::from_u8(20, 10, 10, 0.9),
+        );
+
+        draw_text(
+            draw_queue,
+            ODDS_PANEL_X + 1,
+            ODDS_PANEL_Y + 1,
+            RichText::new(format!(
+                "{:<11}{:>11}",
+                "NEXT SPIN",
+                format!("EV ~${:.1}", odds.expected_coins())
+            ))
+            .with_fg(Rgba::from_u8(255, 255, 155, 1.0))
+            .with_bold(true),
+        );
+
+        for (index, (poker_hand, chance)) in rows.iter().enumerate() {
+            let chance_text: String = if *chance < 0.001 {
+                "<0.1%".to_string()
+            } else {
+                format!("~{:.1}%", chance * 100.0)
+            };
+
+            draw_text(
+                draw_queue,
+                ODDS_PANEL_X + 1,
+                ODDS_PANEL_Y + 3 + index as u16,
+                RichText::new(format!("{:<15}{chance_text:>7}", poker_hand.repr()))
+                    .with_fg(Rgba::from_u8(220, 220, 220, 1.0)),
+            );
+        }
+
+        draw_text(
+            draw_queue,
+            ODDS_PANEL_X + 1,
+            ODDS_PANEL_Y + 3 + rows.len() as u16,
+            RichText::new(format!(
+                "{:>22}",
+                format!("from {} spins", odds.sample_count)
+            ))
+            .with_fg(Rgba::from_u8(150, 150, 150, 1.0))
+            .with_dim(true),
+        );
+    });
+}
@@ -0,0 +1,82 @@
+use crate::{
+    context::Context,
+    poker_hand::{PLAYS_PER_HAND_LEVEL, PokerHand, hand_level, hand_payout},
+    renderer::{DrawCall, Rgba, RichText, draw_rect, draw_text, point_in_rect},
+    utils::center_text_unicode,
+};
+
+pub const PAYTABLE_PANEL_X: u16 = 1;
+pub const PAYTABLE_PANEL_Y: u16 = 11;
+pub const PAYTABLE_PANEL_WIDTH: u16 = 35;
+pub const PAYTABLE_PANEL_HEIGHT: u16 = 18;
+
+/// Overlay listing every poker hand with its level, chips and mult, toggled with `p`.
+pub fn draw_paytable(draw_queue: &mut Vec<DrawCall>, ctx: &Context) {
+    if !ctx.paytable_visible {
+        return;
+    }
+
+    draw_rect(
+        draw_queue,
+        PAYTABLE_PANEL_X as i16,
+        PAYTABLE_PANEL_Y as i16,
+        PAYTABLE_PANEL_WIDTH,
+        PAYTABLE_PANEL_HEIGHT,
+        Rgba::from_u8(20, 10, 10, 0.95),
+    );
+
+    draw_text(
+        draw_queue,
+        PAYTABLE_PANEL_X,
+        PAYTABLE_PANEL_Y + 1,
+        RichText::new(center_text_unicode(
+            "PAYTABLE".to_string(),
+            PAYTABLE_PANEL_WIDTH as usize,
+        ))
+        .with_fg(Rgba::from_u8(255, 210, 140, 1.0))
+        .with_bold(true),
+    );
+
+    // Strongest hands on top
+    for (row_index, poker_hand) in PokerHand::iter().rev().enumerate() {
+        let level: i32 = hand_level(ctx, poker_hand);
+        let play_count: i32 = ctx.hand_play_counts.get(&poker_hand).copied().unwrap_or(0);
+
+        let text: String = format!(
+            "{:<16}Lv{:<3}{:>4}x{:<2} {}/{PLAYS_PER_HAND_LEVEL}",
+            poker_hand.repr(),
+            level,
+            hand_payout(ctx, poker_hand),
+            poker_hand.base_mult(),
+            play_count % PLAYS_PER_HAND_LEVEL,
+        );
+
+        let fg: Rgba = if level > 1 {
+            Rgba::from_u8(255, 255, 155, 1.0)
+        } else {
+            Rgba::from_u8(200, 200, 200, 1.0)
+        };
+
+        draw_text(
+            draw_queue,
+            PAYTABLE_PANEL_X + 2,
+            PAYTABLE_PANEL_Y + 3 + row_index as u16,
+            RichText::new(text)
+                .with_fg(fg)
+                .with_bold(ctx.poker_hand == Some(poker_hand)),
+        );
+    }
+}
+
+/// Whether the open paytable hides the cell at `x`, `y`, so buttons underneath can't be clicked.
+pub fn paytable_covers(ctx: &Context, x: u16, y: u16) -> bool {
+    ctx.paytable_visible
+        && point_in_rect(
+            x,
+            y,
+            PAYTABLE_PANEL_X,
+            PAYTABLE_PANEL_Y,
+            PAYTABLE_PANEL_WIDTH,
+            PAYTABLE_PANEL_HEIGHT,
+        )
+}
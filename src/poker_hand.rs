@@ -5,7 +5,7 @@ use crate::{
     context::Context,
 };
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash, Debug)]
 pub enum PokerHand {
     HighCard,
     Pair,
@@ -23,6 +23,25 @@ pub enum PokerHand {
 }
 
 impl PokerHand {
+    pub fn iter() -> std::array::IntoIter<PokerHand, 13> {
+        [
+            PokerHand::HighCard,
+            PokerHand::Pair,
+            PokerHand::TwoPair,
+            PokerHand::ThreeOfAKind,
+            PokerHand::Straight,
+            PokerHand::Flush,
+            PokerHand::FullHouse,
+            PokerHand::FourOfAKind,
+            PokerHand::StraightFlush,
+            PokerHand::RoyalFlush,
+            PokerHand::FiveOfAKind,
+            PokerHand::FlushHouse,
+            PokerHand::FlushFive,
+        ]
+        .into_iter()
+    }
+
     pub fn repr(&self) -> &'static str {
         match self {
             PokerHand::HighCard => "High Card",
@@ -58,6 +77,71 @@ impl PokerHand {
             PokerHand::HighCard => 0,
         }
     }
+
+    /// Mult the chips of a played hand start out with.
+    pub fn base_mult(&self) -> i32 {
+        match self {
+            PokerHand::FlushFive => 5,
+            PokerHand::FlushHouse => 5,
+            PokerHand::FiveOfAKind => 4,
+            PokerHand::RoyalFlush => 4,
+            PokerHand::StraightFlush => 4,
+            PokerHand::FourOfAKind => 3,
+            PokerHand::FullHouse => 2,
+            PokerHand::Flush => 2,
+            PokerHand::Straight => 2,
+            PokerHand::ThreeOfAKind => 2,
+            PokerHand::TwoPair => 1,
+            PokerHand::Pair => 1,
+            PokerHand::HighCard => 1,
+        }
+    }
+
+    /// Chips gained on every level above the first.
+    pub fn level_bonus(&self) -> i32 {
+        match self {
+            PokerHand::FlushFive => 40,
+            PokerHand::FlushHouse => 40,
+            PokerHand::FiveOfAKind => 35,
+            PokerHand::RoyalFlush => 35,
+            PokerHand::StraightFlush => 30,
+            PokerHand::FourOfAKind => 25,
+            PokerHand::FullHouse => 20,
+            PokerHand::Flush => 15,
+            PokerHand::Straight => 15,
+            PokerHand::ThreeOfAKind => 10,
+            PokerHand::TwoPair => 10,
+            PokerHand::Pair => 5,
+            PokerHand::HighCard => 5,
+        }
+    }
+}
+
+/// Plays of the same poker hand it takes to level it up.
+pub const PLAYS_PER_HAND_LEVEL: i32 = 3;
+
+/// Hands start the run at level 1.
+pub fn hand_level(ctx: &Context, poker_hand: PokerHand) -> i32 {
+    ctx.hand_levels.get(&poker_hand).copied().unwrap_or(1)
+}
+
+/// Base chips a poker hand pays out at its current level.
+pub fn hand_payout(ctx: &Context, poker_hand: PokerHand) -> i32 {
+    poker_hand.coin_value() + poker_hand.level_bonus() * (hand_level(ctx, poker_hand) - 1)
+}
+
+pub fn level_up_hand(ctx: &mut Context, poker_hand: PokerHand) {
+    *ctx.hand_levels.entry(poker_hand).or_insert(1) += 1;
+}
+
+/// Counts a played hand, leveling it up every `PLAYS_PER_HAND_LEVEL` plays.
+pub fn record_played_hand(ctx: &mut Context, poker_hand: PokerHand) {
+    let play_count: &mut i32 = ctx.hand_play_counts.entry(poker_hand).or_insert(0);
+    *play_count += 1;
+
+    if *play_count % PLAYS_PER_HAND_LEVEL == 0 {
+        level_up_hand(ctx, poker_hand);
+    }
 }
 
 // Helper functions that don't modify the original enums
@@ -123,8 +207,178 @@ pub fn update_current_poker_hand(ctx: &mut Context) {
     ctx.poker_hand = Some(poker_hand);
 }
 
+/// Evaluates the best poker hand the cards make, wild cards take whichever
+/// suit and rank give the strongest hand.
 pub fn eval_poker_hand(cards_: &[&Card]) -> (PokerHand, Vec<Card>) {
     let cards: Vec<Card> = cards_.iter().map(|c| **c).collect();
+
+    if cards.iter().all(|card| card.wild.is_none()) {
+        return eval_fixed_poker_hand(cards);
+    }
+
+    let mut best: Option<(PokerHand, i32, Vec<Card>)> = None;
+
+    for suit in wild_suit_candidates(&cards) {
+        let mut suited_cards: Vec<Card> = cards.clone();
+        for card in suited_cards.iter_mut().filter(|card| card.has_wild_suit()) {
+            card.suit = suit;
+        }
+
+        let wild_rank_card_indexes: Vec<usize> = wild_rank_card_order(&suited_cards);
+
+        for ranks in wild_rank_candidates(&suited_cards) {
+            let mut resolved_cards: Vec<Card> = suited_cards.clone();
+            for (&index, rank) in wild_rank_card_indexes.iter().zip(ranks) {
+                resolved_cards[index].rank = rank;
+            }
+
+            let (poker_hand, scoring_cards): (PokerHand, Vec<Card>) =
+                eval_fixed_poker_hand(resolved_cards);
+            let scoring_value: i32 = scoring_cards
+                .iter()
+                .map(|card| card.rank.coin_value() as i32)
+                .sum();
+
+            let is_better: bool = best.as_ref().is_none_or(|(best_hand, best_value, _)| {
+                (poker_hand, scoring_value) > (*best_hand, *best_value)
+            });
+
+            if is_better {
+                best = Some((poker_hand, scoring_value, scoring_cards));
+            }
+        }
+    }
+
+    let (poker_hand, _, scoring_cards) = best.expect("At least one candidate is evaluated");
+    (poker_hand, scoring_cards)
+}
+
+/// Suits only matter for flushes, so every wild suit takes the same one, and only suits
+/// that can still reach a flush are worth trying.
+fn wild_suit_candidates(cards: &[Card]) -> Vec<Suit> {
+    let wild_suit_count: usize = cards.iter().filter(|card| card.has_wild_suit()).count();
+    if wild_suit_count == 0 {
+        return vec![Suit::Spade];
+    }
+
+    let flush_suits: Vec<Suit> = Suit::iter()
+        .filter(|suit| {
+            let suit_count: usize = cards
+                .iter()
+                .filter(|card| !card.has_wild_suit() && card.suit == *suit)
+                .count();
+            suit_count + wild_suit_count >= 5
+        })
+        .collect();
+
+    if flush_suits.is_empty() {
+        vec![Suit::Spade]
+    } else {
+        flush_suits
+    }
+}
+
+fn rank_from_straight_value(value: i32) -> Rank {
+    // 1 is the ace of an ace-low straight
+    let value: i32 = if value == 1 { 14 } else { value };
+    Rank::iter()
+        .find(|rank| rank_straight_value(*rank) == value)
+        .expect("Straight values cover every rank")
+}
+
+/// Ranks for the wild rank cards, most important first, that can make the best hand:
+/// - up to five of one rank, which beats splitting them for every n-of-a-kind hand
+/// - filling the gaps of every straight
+/// - three of one rank and two of another, the one split that pays off, as a Flush House
+///
+/// Hands count ranks exactly, so wilds beyond the pattern go to ranks nobody holds.
+fn wild_rank_candidates(cards: &[Card]) -> Vec<Vec<Rank>> {
+    let wild_rank_count: usize = cards.iter().filter(|card| card.has_wild_rank()).count();
+    if wild_rank_count == 0 {
+        return vec![vec![]];
+    }
+
+    let mut rank_counts: [usize; 13] = [0; 13];
+    for card in cards.iter().filter(|card| !card.has_wild_rank()) {
+        rank_counts[rank_to_index(card.rank)] += 1;
+    }
+    let rank_count = |rank: Rank| rank_counts[rank_to_index(rank)];
+
+    // Highest ranks first, so leftovers add the most value
+    let fill_with_spare_ranks = |mut pattern: Vec<Rank>| -> Option<Vec<Rank>> {
+        let spare_ranks: Vec<Rank> = Rank::iter()
+            .filter(|rank| rank_count(*rank) == 0 && !pattern.contains(rank))
+            .collect();
+        let leftover_count: usize = wild_rank_count.checked_sub(pattern.len())?;
+        pattern.extend(spare_ranks.into_iter().take(leftover_count));
+        (pattern.len() == wild_rank_count).then_some(pattern)
+    };
+
+    // Ranks nobody holds are only worth it as the highest one
+    let mut target_ranks: Vec<Rank> = Rank::iter().filter(|rank| rank_count(*rank) > 0).collect();
+    if !target_ranks.contains(&Rank::Ace) {
+        target_ranks.push(Rank::Ace);
+    }
+
+    let mut candidates: Vec<Vec<Rank>> = vec![];
+
+    for &rank in &target_ranks {
+        let needed: usize = 5usize.saturating_sub(rank_count(rank)).min(wild_rank_count);
+        candidates.extend(fill_with_spare_ranks(vec![rank; needed]));
+    }
+
+    for high in 5..=14 {
+        let gaps: Vec<Rank> = (high - 4..=high)
+            .rev()
+            .map(rank_from_straight_value)
+            .filter(|rank| rank_count(*rank) == 0)
+            .collect();
+        candidates.extend(fill_with_spare_ranks(gaps));
+    }
+
+    let mut suit_counts: [usize; 4] = [0; 4];
+    for card in cards {
+        suit_counts[suit_to_index(card.suit)] += 1;
+    }
+    let flush_possible: bool = suit_counts.iter().any(|&count| count >= 5);
+
+    if flush_possible {
+        for &three_rank in &target_ranks {
+            for &pair_rank in target_ranks.iter().filter(|rank| **rank != three_rank) {
+                let (Some(three_needed), Some(pair_needed)) = (
+                    3usize.checked_sub(rank_count(three_rank)),
+                    2usize.checked_sub(rank_count(pair_rank)),
+                ) else {
+                    continue;
+                };
+
+                let pattern: Vec<Rank> = std::iter::repeat_n(three_rank, three_needed)
+                    .chain(std::iter::repeat_n(pair_rank, pair_needed))
+                    .collect();
+                candidates.extend(fill_with_spare_ranks(pattern));
+            }
+        }
+    }
+
+    candidates
+}
+
+/// Indexes of the wild rank cards, the ones in the most common suit first. Only cards of
+/// the flush suit score in a flush, so they get the ranks that matter most.
+fn wild_rank_card_order(cards: &[Card]) -> Vec<usize> {
+    let mut suit_counts: [usize; 4] = [0; 4];
+    for card in cards {
+        suit_counts[suit_to_index(card.suit)] += 1;
+    }
+
+    let mut indexes: Vec<usize> = (0..cards.len())
+        .filter(|&index| cards[index].has_wild_rank())
+        .collect();
+    indexes.sort_by_key(|&index| Reverse(suit_counts[suit_to_index(cards[index].suit)]));
+    indexes
+}
+
+fn eval_fixed_poker_hand(cards: Vec<Card>) -> (PokerHand, Vec<Card>) {
     let mut suit_counts: [u8; 4] = [0u8; 4];
     let mut rank_counts: [u8; 13] = [0u8; 13];
     let mut cards_by_suit: Vec<Vec<Card>> = vec![Vec::new(); 4];
@@ -315,12 +569,14 @@ struct StraightInfo {
 
 fn check_straight(cards: &[Card]) -> StraightInfo {
     // Convert to bitmask for fast straight checking
-    let mut rank_present: [bool; 15] = [false; 15]; // Index 2-14 for ranks (2=2, 14=Ace high)
+    let mut rank_present: [bool; 15] = [false; 15]; // Index 1-14 for ranks (1=Ace low, 2=2, 14=Ace high)
 
     for card in cards {
         let value: i32 = rank_straight_value(card.rank);
         rank_present[value as usize] = true;
     }
+    // Aces also count as the low end of A,2,3,4,5
+    rank_present[1] = rank_present[14];
 
     // Check for Ace-low straight (A,2,3,4,5)
     let ace_low_straight = rank_present[14]
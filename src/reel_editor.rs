@@ -0,0 +1,372 @@
+use crate::{
+    button::{Button, ButtonId},
+    card::{Card, Rank, Suit, draw_calls_playing_card_small},
+    context::Context,
+    renderer::{DrawCall, Rgba, RichText, draw_rect, draw_text},
+    shop::{SHOP_PANEL_HEIGHT, SHOP_PANEL_WIDTH, SHOP_PANEL_X, SHOP_PANEL_Y},
+    slot_machine::{ReelCard, SlotMachineColumn, check_reel_limits},
+    utils::center_text_unicode,
+};
+
+const GRID_ORIGIN_X: u16 = SHOP_PANEL_X + 2;
+const GRID_ORIGIN_Y: u16 = SHOP_PANEL_Y + 2;
+const GRID_COLUMN_COUNT: usize = 8;
+const GRID_X_SPACING: u16 = 4;
+/// Row of the picker for the card ADD puts on the reel, below the largest reel's grid.
+const NEW_CARD_ROW_Y: u16 = SHOP_PANEL_Y + SHOP_PANEL_HEIGHT - 5;
+const NEW_CARD_X: u16 = SHOP_PANEL_X + 14;
+
+pub const ADD_CARD_COST: i32 = 10;
+pub const DUPLICATE_CARD_COST: i32 = 15;
+pub const WEIGHT_CARD_COST: i32 = 20;
+pub const REMOVE_CARD_COST: i32 = 8;
+
+/// State of the shop's reel editor, which edits one slot machine column at a time.
+pub struct ReelEditor {
+    pub column_index: usize,
+    pub selected_index: Option<usize>,
+    /// Card the ADD edit puts on the reel, picked by rank and suit.
+    pub new_card: Card,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ReelEditOp {
+    Add,
+    Duplicate,
+    Weight,
+    Remove,
+}
+
+impl ReelEditOp {
+    fn label(&self) -> &'static str {
+        match self {
+            ReelEditOp::Add => "ADD",
+            ReelEditOp::Duplicate => "DUPE",
+            ReelEditOp::Weight => "WT+",
+            ReelEditOp::Remove => "DEL",
+        }
+    }
+
+    fn cost(&self) -> i32 {
+        match self {
+            ReelEditOp::Add => ADD_CARD_COST,
+            ReelEditOp::Duplicate => DUPLICATE_CARD_COST,
+            ReelEditOp::Weight => WEIGHT_CARD_COST,
+            ReelEditOp::Remove => REMOVE_CARD_COST,
+        }
+    }
+}
+
+pub fn open_reel_editor(ctx: &mut Context) {
+    ctx.reel_editor = Some(ReelEditor {
+        column_index: 0,
+        selected_index: None,
+        new_card: Card::new(Suit::Spade, Rank::Ace),
+    });
+}
+
+fn close_reel_editor(ctx: &mut Context) {
+    ctx.reel_editor = None;
+}
+
+fn edited_column(ctx: &Context) -> Option<&SlotMachineColumn> {
+    ctx.reel_editor
+        .as_ref()
+        .and_then(|editor| ctx.slot_machine.columns.get(editor.column_index))
+}
+
+/// Where ADD puts the new card, right after the selected card or at the end of the reel.
+fn new_card_index(ctx: &Context) -> Option<usize> {
+    let editor: &ReelEditor = ctx.reel_editor.as_ref()?;
+    let reel_size: usize = edited_column(ctx)?.cards.len();
+    Some(editor.selected_index.map_or(reel_size, |index| index + 1))
+}
+
+/// The edited reel once `op` is applied, `None` if it can't be.
+fn edited_reel(ctx: &Context, op: ReelEditOp) -> Option<Vec<ReelCard>> {
+    let editor: &ReelEditor = ctx.reel_editor.as_ref()?;
+    let mut cards: Vec<ReelCard> = edited_column(ctx)?.cards.clone();
+
+    match op {
+        ReelEditOp::Add => {
+            cards.insert(
+                new_card_index(ctx)?,
+                ReelCard {
+                    card: editor.new_card,
+                    weight: 1,
+                },
+            );
+        }
+        ReelEditOp::Duplicate => {
+            let selected_index: usize = editor.selected_index?;
+            let card: Card = cards.get(selected_index)?.card;
+            cards.insert(selected_index + 1, ReelCard { card, weight: 1 });
+        }
+        ReelEditOp::Weight => {
+            cards.get_mut(editor.selected_index?)?.weight += 1;
+        }
+        ReelEditOp::Remove => {
+            let selected_index: usize = editor.selected_index?;
+            cards.get(selected_index)?;
+            cards.remove(selected_index);
+        }
+    }
+
+    check_reel_limits(&cards).ok().map(|()| cards)
+}
+
+fn can_apply_reel_edit_op(ctx: &Context, op: ReelEditOp) -> bool {
+    ctx.coins >= op.cost() && edited_reel(ctx, op).is_some()
+}
+
+fn apply_reel_edit_op(ctx: &mut Context, op: ReelEditOp) {
+    if ctx.coins < op.cost() {
+        return;
+    }
+    let Some(cards) = edited_reel(ctx, op) else {
+        return;
+    };
+
+    let new_card_index: Option<usize> = new_card_index(ctx);
+
+    let editor: &mut ReelEditor = ctx.reel_editor.as_mut().expect("Checked above");
+    editor.selected_index = match op {
+        ReelEditOp::Add => new_card_index,
+        ReelEditOp::Remove => None,
+        ReelEditOp::Duplicate | ReelEditOp::Weight => editor.selected_index,
+    };
+    ctx.slot_machine.columns[editor.column_index].cards = cards;
+    ctx.coins -= op.cost();
+}
+
+/// The value `step` places after `current` in `values`, wrapping around.
+fn cycled<T: Copy + PartialEq>(values: &[T], current: T, step: isize) -> T {
+    let index: usize = values
+        .iter()
+        .position(|value| *value == current)
+        .unwrap_or(0);
+    values[(index as isize + step).rem_euclid(values.len() as isize) as usize]
+}
+
+fn cycle_new_card_rank(ctx: &mut Context, step: isize) {
+    if let Some(editor) = ctx.reel_editor.as_mut() {
+        let ranks: Vec<Rank> = Rank::iter().collect();
+        editor.new_card.rank = cycled(&ranks, editor.new_card.rank, step);
+    }
+}
+
+fn cycle_new_card_suit(ctx: &mut Context, step: isize) {
+    if let Some(editor) = ctx.reel_editor.as_mut() {
+        let suits: Vec<Suit> = Suit::iter().collect();
+        editor.new_card.suit = cycled(&suits, editor.new_card.suit, step);
+    }
+}
+
+fn cycle_edited_column(ctx: &mut Context, step: isize) {
+    let column_count: isize = ctx.slot_machine.columns.len() as isize;
+    if let Some(editor) = ctx.reel_editor.as_mut() {
+        editor.column_index =
+            (editor.column_index as isize + step).rem_euclid(column_count) as usize;
+        editor.selected_index = None;
+    }
+}
+
+fn grid_position(card_index: usize) -> (u16, u16) {
+    (
+        GRID_ORIGIN_X + (card_index % GRID_COLUMN_COUNT) as u16 * GRID_X_SPACING,
+        GRID_ORIGIN_Y + (card_index / GRID_COLUMN_COUNT) as u16,
+    )
+}
+
+pub fn push_reel_editor_buttons(buttons: &mut Vec<Button>, ctx: &Context) {
+    let Some(column) = edited_column(ctx) else {
+        return;
+    };
+
+    // Card selection
+    for card_index in 0..column.cards.len() {
+        let (x, y) = grid_position(card_index);
+        buttons.push(Button {
+            id: ButtonId::SelectReelCard { index: card_index },
+            x,
+            y,
+            w: 3,
+            h: 1,
+            text: "".to_string(),
+            color: Rgba::from_u8(0, 0, 0, 0.0),
+            on_click: Box::new(move |ctx: &mut Context| {
+                if let Some(editor) = ctx.reel_editor.as_mut() {
+                    editor.selected_index = Some(card_index);
+                }
+            }),
+            enabled_when: Box::new(|_| true),
+            allow_rmb: false,
+        });
+    }
+
+    // Reel selection
+    for (x, text, step) in [
+        (SHOP_PANEL_X + 1, "<", -1),
+        (SHOP_PANEL_X + SHOP_PANEL_WIDTH - 4, ">", 1),
+    ] {
+        buttons.push(Button {
+            id: ButtonId::CycleEditedColumn { step },
+            x,
+            y: SHOP_PANEL_Y,
+            w: 3,
+            h: 1,
+            text: text.to_string(),
+            color: Rgba::from_u8(255, 200, 160, 1.0),
+            on_click: Box::new(move |ctx: &mut Context| cycle_edited_column(ctx, step)),
+            enabled_when: Box::new(|_| true),
+            allow_rmb: false,
+        });
+    }
+
+    // New card picker, rank on the arrows around the card and suit on its own button
+    for (x, text, step) in [(NEW_CARD_X - 4, "<", -1), (NEW_CARD_X + 4, ">", 1)] {
+        buttons.push(Button {
+            id: ButtonId::CycleNewCardRank { step },
+            x,
+            y: NEW_CARD_ROW_Y,
+            w: 3,
+            h: 1,
+            text: text.to_string(),
+            color: Rgba::from_u8(255, 200, 160, 1.0),
+            on_click: Box::new(move |ctx: &mut Context| cycle_new_card_rank(ctx, step)),
+            enabled_when: Box::new(|_| true),
+            allow_rmb: false,
+        });
+    }
+
+    buttons.push(Button {
+        id: ButtonId::CycleNewCardSuit,
+        x: NEW_CARD_X + 8,
+        y: NEW_CARD_ROW_Y,
+        w: 6,
+        h: 1,
+        text: "SUIT".to_string(),
+        color: Rgba::from_u8(255, 200, 160, 1.0),
+        on_click: Box::new(|ctx: &mut Context| cycle_new_card_suit(ctx, 1)),
+        enabled_when: Box::new(|_| true),
+        allow_rmb: false,
+    });
+
+    let bottom_y: u16 = SHOP_PANEL_Y + SHOP_PANEL_HEIGHT - 2;
+    let ops: [ReelEditOp; 4] = [
+        ReelEditOp::Add,
+        ReelEditOp::Duplicate,
+        ReelEditOp::Weight,
+        ReelEditOp::Remove,
+    ];
+
+    for (op_index, op) in ops.into_iter().enumerate() {
+        buttons.push(Button {
+            id: ButtonId::ReelEdit(op),
+            x: SHOP_PANEL_X + 1 + op_index as u16 * 7,
+            y: bottom_y,
+            w: 6,
+            h: 1,
+            text: op.label().to_string(),
+            color: Rgba::from_u8(255, 150, 90, 1.0),
+            on_click: Box::new(move |ctx: &mut Context| apply_reel_edit_op(ctx, op)),
+            enabled_when: Box::new(move |ctx: &Context| can_apply_reel_edit_op(ctx, op)),
+            allow_rmb: false,
+        });
+    }
+
+    buttons.push(Button {
+        id: ButtonId::CloseReelEditor,
+        x: SHOP_PANEL_X + 29,
+        y: bottom_y,
+        w: 6,
+        h: 1,
+        text: "BACK".to_string(),
+        color: Rgba::from_u8(160, 210, 140, 1.0),
+        on_click: Box::new(close_reel_editor),
+        enabled_when: Box::new(|_| true),
+        allow_rmb: false,
+    });
+}
+
+pub fn draw_reel_editor(draw_queue: &mut Vec<DrawCall>, ctx: &Context) {
+    let (Some(editor), Some(column)) = (ctx.reel_editor.as_ref(), edited_column(ctx)) else {
+        return;
+    };
+
+    draw_rect(
+        draw_queue,
+        SHOP_PANEL_X as i16,
+        SHOP_PANEL_Y as i16,
+        SHOP_PANEL_WIDTH,
+        SHOP_PANEL_HEIGHT,
+        Rgba::from_u8(20, 10, 10, 0.95),
+    );
+
+    draw_text(
+        draw_queue,
+        SHOP_PANEL_X,
+        SHOP_PANEL_Y,
+        RichText::new(center_text_unicode(
+            format!(
+                "REEL {}/{} ({} cards)",
+                editor.column_index + 1,
+                ctx.slot_machine.columns.len(),
+                column.cards.len()
+            ),
+            SHOP_PANEL_WIDTH as usize,
+        ))
+        .with_fg(Rgba::from_u8(255, 210, 140, 1.0))
+        .with_bold(true),
+    );
+
+    let selection_color: Rgba = Rgba::from_u8(255, 230, 80, 1.0);
+
+    for (card_index, reel_card) in column.cards.iter().enumerate() {
+        let (x, y) = grid_position(card_index);
+        let mut draw_call: DrawCall = draw_calls_playing_card_small(x, y, &reel_card.card);
+
+        if editor.selected_index == Some(card_index) {
+            draw_call.rich_text.bg = draw_call.rich_text.bg.lerp(selection_color, 0.8);
+        }
+
+        draw_queue.push(draw_call);
+
+        // Weight is shown in the gap after the card
+        if reel_card.weight > 1 {
+            draw_text(
+                draw_queue,
+                x + 3,
+                y,
+                RichText::new(format!("{}", reel_card.weight))
+                    .with_fg(selection_color)
+                    .with_bold(true),
+            );
+        }
+    }
+
+    draw_text(
+        draw_queue,
+        SHOP_PANEL_X + 2,
+        NEW_CARD_ROW_Y,
+        RichText::new("NEW").with_fg(Rgba::from_u8(200, 200, 200, 1.0)),
+    );
+    draw_queue.push(draw_calls_playing_card_small(
+        NEW_CARD_X,
+        NEW_CARD_ROW_Y,
+        &editor.new_card,
+    ));
+
+    draw_text(
+        draw_queue,
+        SHOP_PANEL_X,
+        SHOP_PANEL_Y + SHOP_PANEL_HEIGHT - 4,
+        RichText::new(center_text_unicode(
+            format!(
+                "add ${ADD_CARD_COST} dupe ${DUPLICATE_CARD_COST} weight ${WEIGHT_CARD_COST} del ${REMOVE_CARD_COST}"
+            ),
+            SHOP_PANEL_WIDTH as usize,
+        ))
+        .with_fg(Rgba::from_u8(200, 200, 200, 1.0)),
+    );
+}
@@ -0,0 +1,237 @@
+use crate::{
+    card::{Card, Rank, Suit},
+    constants::SIDEBAR_BORDER_X,
+    context::Context,
+    poker_hand::PokerHand,
+    renderer::{
+        DrawCall, Layer, Rgba, RichText, draw_in_layer, draw_rect, draw_text, point_in_rect,
+    },
+    scoring::{ScoreSource, is_scoring_source_active},
+    utils::center_text_unicode,
+};
+
+pub const MAX_RELIC_COUNT: usize = 5;
+pub const RELICS_ORIGIN_X: u16 = SIDEBAR_BORDER_X + 2;
+pub const RELICS_ORIGIN_Y: u16 = 20;
+pub const RELIC_TILE_WIDTH: u16 = 3;
+
+const TOOLTIP_X: u16 = 1;
+const TOOLTIP_Y: u16 = 24;
+const TOOLTIP_WIDTH: u16 = SIDEBAR_BORDER_X - 3;
+const TOOLTIP_HEIGHT: u16 = 4;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Relic {
+    GoldenTooth,
+    HeartLocket,
+    RoyalSeal,
+    LoadedDice,
+    AceInTheHole,
+    FourLeafClover,
+    PiggyBank,
+}
+
+impl Relic {
+    pub fn iter() -> std::array::IntoIter<Relic, 7> {
+        [
+            Relic::GoldenTooth,
+            Relic::HeartLocket,
+            Relic::RoyalSeal,
+            Relic::LoadedDice,
+            Relic::AceInTheHole,
+            Relic::FourLeafClover,
+            Relic::PiggyBank,
+        ]
+        .into_iter()
+    }
+
+    pub fn repr(&self) -> &'static str {
+        match self {
+            Relic::GoldenTooth => "$",
+            Relic::HeartLocket => "♥",
+            Relic::RoyalSeal => "K",
+            Relic::LoadedDice => "x",
+            Relic::AceInTheHole => "A",
+            Relic::FourLeafClover => "#",
+            Relic::PiggyBank => "%",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Relic::GoldenTooth => "Golden Tooth",
+            Relic::HeartLocket => "Heart Locket",
+            Relic::RoyalSeal => "Royal Seal",
+            Relic::LoadedDice => "Loaded Dice",
+            Relic::AceInTheHole => "Ace in the Hole",
+            Relic::FourLeafClover => "Four-Leaf Clover",
+            Relic::PiggyBank => "Piggy Bank",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Relic::GoldenTooth => "+10 chips on every played hand",
+            Relic::HeartLocket => "+4 chips per scoring ♥ card",
+            Relic::RoyalSeal => "+8 chips per scoring J, Q or K",
+            Relic::LoadedDice => "x2 mult on Pair and Two Pair",
+            Relic::AceInTheHole => "x1.5 mult if an Ace scores",
+            Relic::FourLeafClover => "+1 luck on every spin",
+            Relic::PiggyBank => "+3 coins per burned card",
+        }
+    }
+
+    pub fn color(&self) -> Rgba {
+        match self {
+            Relic::GoldenTooth => Rgba::from_u8(255, 210, 90, 1.0),
+            Relic::HeartLocket => Rgba::from_u8(255, 120, 140, 1.0),
+            Relic::RoyalSeal => Rgba::from_u8(180, 140, 255, 1.0),
+            Relic::LoadedDice => Rgba::from_u8(240, 240, 240, 1.0),
+            Relic::AceInTheHole => Rgba::from_u8(120, 200, 255, 1.0),
+            Relic::FourLeafClover => Rgba::from_u8(150, 255, 150, 1.0),
+            Relic::PiggyBank => Rgba::from_u8(255, 170, 200, 1.0),
+        }
+    }
+
+    /// Chips added to a played hand.
+    pub fn flat_bonus(&self, _poker_hand: PokerHand, scoring_cards: &[Card]) -> i32 {
+        match self {
+            Relic::GoldenTooth => 10,
+            Relic::HeartLocket => {
+                4 * scoring_cards
+                    .iter()
+                    .filter(|card| card.suit == Suit::Heart)
+                    .count() as i32
+            }
+            Relic::RoyalSeal => {
+                8 * scoring_cards
+                    .iter()
+                    .filter(|card| matches!(card.rank, Rank::Jack | Rank::Queen | Rank::King))
+                    .count() as i32
+            }
+            _ => 0,
+        }
+    }
+
+    /// Factor the mult of a played hand is multiplied by.
+    pub fn multiplier(&self, poker_hand: PokerHand, scoring_cards: &[Card]) -> f32 {
+        match self {
+            Relic::LoadedDice if matches!(poker_hand, PokerHand::Pair | PokerHand::TwoPair) => 2.0,
+            Relic::AceInTheHole if scoring_cards.iter().any(|card| card.rank == Rank::Ace) => 1.5,
+            _ => 1.0,
+        }
+    }
+}
+
+pub fn on_spin_relics(ctx: &mut Context) {
+    for relic in ctx.relics.clone() {
+        if relic == Relic::FourLeafClover {
+            ctx.luck += 1;
+        }
+    }
+}
+
+pub fn on_burn_relics(ctx: &mut Context, burned_card_count: i32) {
+    for relic in ctx.relics.clone() {
+        if relic == Relic::PiggyBank {
+            ctx.coins += 3 * burned_card_count;
+        }
+    }
+}
+
+pub fn unowned_relics(ctx: &Context) -> Vec<Relic> {
+    Relic::iter()
+        .filter(|relic| !ctx.relics.contains(relic))
+        .collect()
+}
+
+fn get_hovered_relic(ctx: &Context) -> Option<&Relic> {
+    ctx.relics.iter().enumerate().find_map(|(index, relic)| {
+        let is_hovered: bool = point_in_rect(
+            ctx.mouse.x,
+            ctx.mouse.y,
+            RELICS_ORIGIN_X + index as u16 * RELIC_TILE_WIDTH,
+            RELICS_ORIGIN_Y,
+            RELIC_TILE_WIDTH,
+            1,
+        );
+        is_hovered.then_some(relic)
+    })
+}
+
+pub fn draw_relics(draw_queue: &mut Vec<DrawCall>, ctx: &Context) {
+    draw_text(
+        draw_queue,
+        RELICS_ORIGIN_X,
+        RELICS_ORIGIN_Y - 1,
+        RichText::new(center_text_unicode(
+            "RELICS".to_string(),
+            (RELIC_TILE_WIDTH * MAX_RELIC_COUNT as u16) as usize,
+        ))
+        .with_fg(Rgba::from_u8(150, 120, 110, 1.0)),
+    );
+
+    for index in 0..MAX_RELIC_COUNT {
+        let x: u16 = RELICS_ORIGIN_X + index as u16 * RELIC_TILE_WIDTH;
+
+        let rich_text: RichText = match ctx.relics.get(index) {
+            Some(relic) => {
+                let bg: Rgba = if is_scoring_source_active(ctx, ScoreSource::Relic(index)) {
+                    Rgba::from_u8(255, 255, 255, 1.0)
+                } else {
+                    relic.color()
+                };
+
+                RichText::new(format!(" {} ", relic.repr()))
+                    .with_fg(Rgba::from_u8(0, 0, 0, 1.0))
+                    .with_bg(bg)
+                    .with_bold(true)
+            }
+            None => RichText::new(" · ")
+                .with_fg(Rgba::from_u8(90, 60, 60, 1.0))
+                .with_bg(Rgba::from_u8(0, 0, 0, 0.3)),
+        };
+
+        draw_text(draw_queue, x, RELICS_ORIGIN_Y, rich_text);
+    }
+}
+
+pub fn draw_relic_tooltip(draw_queue: &mut Vec<DrawCall>, ctx: &Context) {
+    let Some(relic) = get_hovered_relic(ctx) else {
+        return;
+    };
+
+    draw_in_layer(draw_queue, Layer::Tooltip, |draw_queue| {
+        draw_rect(
+            draw_queue,
+            TOOLTIP_X as i16,
+            TOOLTIP_Y as i16,
+            TOOLTIP_WIDTH,
+            TOOLTIP_HEIGHT,
+            Rgba::from_u8(20, 10, 10, 0.9),
+        );
+
+        draw_text(
+            draw_queue,
+            TOOLTIP_X,
+            TOOLTIP_Y + 1,
+            RichText::new(center_text_unicode(
+                relic.name().to_string(),
+                TOOLTIP_WIDTH as usize,
+            ))
+            .with_fg(relic.color())
+            .with_bold(true),
+        );
+
+        draw_text(
+            draw_queue,
+            TOOLTIP_X,
+            TOOLTIP_Y + 2,
+            RichText::new(center_text_unicode(
+                relic.description().to_string(),
+                TOOLTIP_WIDTH as usize,
+            ))
+            .with_fg(Rgba::from_u8(220, 220, 220, 1.0)),
+        );
+    });
+}
@@ -1,4 +1,7 @@
 use crossterm::style::{Attribute, Attributes, Color, ContentStyle};
+use unicode_width::UnicodeWidthChar;
+
+use crate::color_depth::{ColorDepth, quantize_color};
 
 // TODO: look into luma based desaturation
 
@@ -118,11 +121,64 @@ impl From<Rgba> for Hsl {
     }
 }
 
+/// Text attributes besides bold, as a set of flags.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TextAttributes(u8);
+
+impl TextAttributes {
+    pub const ITALIC: TextAttributes = TextAttributes(1);
+    pub const UNDERLINE: TextAttributes = TextAttributes(1 << 1);
+    pub const DIM: TextAttributes = TextAttributes(1 << 2);
+    pub const REVERSE: TextAttributes = TextAttributes(1 << 3);
+    pub const BLINK: TextAttributes = TextAttributes(1 << 4);
+    pub const STRIKETHROUGH: TextAttributes = TextAttributes(1 << 5);
+
+    pub fn iter() -> std::array::IntoIter<TextAttributes, 6> {
+        [
+            TextAttributes::ITALIC,
+            TextAttributes::UNDERLINE,
+            TextAttributes::DIM,
+            TextAttributes::REVERSE,
+            TextAttributes::BLINK,
+            TextAttributes::STRIKETHROUGH,
+        ]
+        .into_iter()
+    }
+
+    /// Name of a single flag.
+    pub fn repr(&self) -> &'static str {
+        match *self {
+            TextAttributes::ITALIC => "italic",
+            TextAttributes::UNDERLINE => "underline",
+            TextAttributes::DIM => "dim",
+            TextAttributes::REVERSE => "reverse",
+            TextAttributes::BLINK => "blink",
+            TextAttributes::STRIKETHROUGH => "strikethrough",
+            _ => "mixed",
+        }
+    }
+
+    pub fn contains(&self, flag: TextAttributes) -> bool {
+        self.0 & flag.0 == flag.0
+    }
+
+    pub fn set(&mut self, flag: TextAttributes, value: bool) {
+        if value {
+            self.0 |= flag.0;
+        } else {
+            self.0 &= !flag.0;
+        }
+    }
+}
+
 pub struct RichText {
     pub text: String,
     pub fg: Rgba,
     pub bg: Rgba,
     pub bold: bool,
+    pub attributes: TextAttributes,
+    /// Underlines take the fg color without one.
+    pub underline_color: Option<Rgba>,
 }
 
 impl RichText {
@@ -142,6 +198,8 @@ impl RichText {
                 a: 0.0,
             },
             bold: false,
+            attributes: TextAttributes::default(),
+            underline_color: None,
         }
     }
 
@@ -159,14 +217,85 @@ impl RichText {
         self.bold = value;
         self
     }
+
+    pub fn with_italic(mut self, value: bool) -> Self {
+        self.attributes.set(TextAttributes::ITALIC, value);
+        self
+    }
+
+    pub fn with_underline(mut self, value: bool) -> Self {
+        self.attributes.set(TextAttributes::UNDERLINE, value);
+        self
+    }
+
+    /// Also turns the underline on.
+    pub fn with_underline_color(mut self, color: Rgba) -> Self {
+        self.attributes.set(TextAttributes::UNDERLINE, true);
+        self.underline_color = Some(color);
+        self
+    }
+
+    pub fn with_dim(mut self, value: bool) -> Self {
+        self.attributes.set(TextAttributes::DIM, value);
+        self
+    }
+
+    /// Swaps fg and bg once on the terminal.
+    pub fn with_reverse(mut self, value: bool) -> Self {
+        self.attributes.set(TextAttributes::REVERSE, value);
+        self
+    }
+
+    pub fn with_blink(mut self, value: bool) -> Self {
+        self.attributes.set(TextAttributes::BLINK, value);
+        self
+    }
+
+    pub fn with_strikethrough(mut self, value: bool) -> Self {
+        self.attributes.set(TextAttributes::STRIKETHROUGH, value);
+        self
+    }
 }
 
-#[derive(Clone)]
+/// How many columns the glyph of a cell spans.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CellWidth {
+    Single,
+    /// First half of a double width glyph, the glyph covers the next cell too.
+    Wide,
+    /// Second half of a double width glyph, drawn by the cell before it.
+    Continuation,
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct Cell {
     pub ch: char,
+    /// Zero width chars following `ch` in the same grapheme, like combining accents.
+    pub combining: String,
+    pub width: CellWidth,
     pub fg: PackedRGB,
     pub bg: PackedRGB,
     pub bold: bool,
+    pub attributes: TextAttributes,
+    pub underline_color: Option<PackedRGB>,
+}
+
+impl Cell {
+    /// What the terminal has to print for this cell, nothing for a continuation.
+    pub fn printed_text(&self) -> String {
+        match self.width {
+            CellWidth::Continuation => String::new(),
+            CellWidth::Single | CellWidth::Wide => format!("{}{}", self.ch, self.combining),
+        }
+    }
+
+    pub fn same_style(&self, other: &Cell) -> bool {
+        self.fg == other.fg
+            && self.bg == other.bg
+            && self.bold == other.bold
+            && self.attributes == other.attributes
+            && self.underline_color == other.underline_color
+    }
 }
 
 pub struct ScreenBuffer {
@@ -176,12 +305,16 @@ pub struct ScreenBuffer {
 }
 
 impl ScreenBuffer {
-    fn new(width: u16, height: u16, default_bg: (u8, u8, u8)) -> Self {
+    pub fn new(width: u16, height: u16, default_bg: (u8, u8, u8)) -> Self {
         let cell: Cell = Cell {
             ch: ' ',
+            combining: String::new(),
+            width: CellWidth::Single,
             fg: pack_rgb(255, 255, 255),
             bg: pack_rgb(default_bg.0, default_bg.1, default_bg.2),
             bold: false,
+            attributes: TextAttributes::default(),
+            underline_color: None,
         };
         Self {
             width,
@@ -209,89 +342,267 @@ impl Screen {
     }
 }
 
+/// Stacking order of draw calls, later layers are composed on top of earlier ones no
+/// matter when their calls were pushed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Layer {
+    /// Board, sidebar, panels and buttons, stacked in push order.
+    #[default]
+    Scene,
+    /// Hover panels like the relic tooltip and the spin odds.
+    Tooltip,
+    /// The card under the mouse while it's dragged, with its shadow.
+    DragPreview,
+    /// Debug readouts like the FPS counter.
+    Overlay,
+}
+
 pub struct DrawCall {
     pub x: u16,
     pub y: u16,
     pub rich_text: RichText,
+    pub layer: Layer,
+}
+
+/// Runs `draw` and lifts every call it pushes to at least `layer`, so calls that already
+/// sit higher, like a nested `draw_in_layer`, stay there.
+pub fn draw_in_layer(
+    draw_queue: &mut Vec<DrawCall>,
+    layer: Layer,
+    draw: impl FnOnce(&mut Vec<DrawCall>),
+) {
+    let start: usize = draw_queue.len();
+    draw(draw_queue);
+
+    for dc in &mut draw_queue[start..] {
+        dc.layer = dc.layer.max(layer);
+    }
 }
 
 pub fn point_in_rect(px: u16, py: u16, x: u16, y: u16, w: u16, h: u16) -> bool {
     px >= x && px < x + w && py >= y && py < y + h
 }
 
-pub fn diff_buffers<'a>(old: &'a ScreenBuffer, new: &'a ScreenBuffer) -> Vec<(u16, u16, &'a Cell)> {
-    let mut diffs = Vec::new();
+/// Unchanged cells a run may bridge, reprinting them is cheaper than moving the cursor.
+const MAX_RUN_GAP: usize = 4;
+
+/// Changed cells of a row, printed with a single cursor move.
+#[derive(Debug)]
+pub struct CellRun<'a> {
+    pub x: u16,
+    pub y: u16,
+    pub cells: &'a [Cell],
+}
+
+/// Groups the cells that changed between two frames into runs per row. Short gaps of
+/// unchanged cells are bridged when they share the style of the cell before them.
+pub fn diff_buffers<'a>(old: &'a ScreenBuffer, new: &'a ScreenBuffer) -> Vec<CellRun<'a>> {
+    let mut runs: Vec<CellRun<'a>> = vec![];
     let h: usize = old.height.min(new.height) as usize;
     let w: usize = old.width.min(new.width) as usize;
 
     for y in 0..h {
-        for x in 0..w {
-            let old_cell = &old.cells[y * old.width as usize + x];
-            let new_cell = &new.cells[y * new.width as usize + x];
-            if old_cell.ch != new_cell.ch
-                || old_cell.fg != new_cell.fg
-                || old_cell.bg != new_cell.bg
-                || old_cell.bold != new_cell.bold
-            {
-                diffs.push((x as u16, y as u16, new_cell));
+        let old_row: &[Cell] = &old.cells[y * old.width as usize..][..w];
+        let new_row: &[Cell] = &new.cells[y * new.width as usize..][..w];
+
+        let changed: Vec<bool> = (0..w)
+            .map(|x| {
+                // A changed continuation can only be redrawn through its glyph's first half
+                let continuation_changed: bool = x + 1 < w
+                    && new_row[x + 1].width == CellWidth::Continuation
+                    && old_row[x + 1] != new_row[x + 1];
+                old_row[x] != new_row[x] || continuation_changed
+            })
+            .collect();
+
+        let mut x: usize = 0;
+        while x < w {
+            if !changed[x] {
+                x += 1;
+                continue;
+            }
+
+            let run_start: usize = x;
+            let mut run_end: usize = x + 1;
+
+            while run_end < w {
+                if changed[run_end] {
+                    run_end += 1;
+                    continue;
+                }
+
+                let gap_end: Option<usize> =
+                    (run_end..w.min(run_end + MAX_RUN_GAP + 1)).find(|&gap_x| changed[gap_x]);
+                let gap_keeps_style = |gap_end: usize| {
+                    new_row[run_end..gap_end]
+                        .iter()
+                        .all(|cell| cell.same_style(&new_row[run_end - 1]))
+                };
+
+                match gap_end {
+                    Some(gap_end) if gap_keeps_style(gap_end) => run_end = gap_end,
+                    _ => break,
+                }
             }
+
+            runs.push(CellRun {
+                x: run_start as u16,
+                y: y as u16,
+                cells: &new_row[run_start..run_end],
+            });
+            x = run_end;
+        }
+    }
+
+    runs
+}
+
+/// Splits text into graphemes with their display width. Zero width chars join the
+/// grapheme before them, anything without a known width takes up a single cell.
+fn layout_graphemes(text: &str) -> Vec<(char, String, u16)> {
+    let mut graphemes: Vec<(char, String, u16)> = vec![];
+
+    for char in text.chars() {
+        let char_width: Option<usize> = char.width();
+
+        match (char_width, graphemes.last_mut()) {
+            (Some(0), Some((_, combining, _))) => combining.push(char),
+            _ => graphemes.push((
+                char,
+                String::new(),
+                char_width.unwrap_or(1).clamp(1, 2) as u16,
+            )),
         }
     }
 
-    diffs
+    graphemes
+}
+
+/// Clears whatever is left of a double width glyph once one of its halves at `index`
+/// gets overwritten.
+fn break_wide_glyph(buf: &mut ScreenBuffer, index: usize) {
+    let other_half_index: usize = match buf.cells[index].width {
+        CellWidth::Single => return,
+        CellWidth::Wide => index + 1,
+        CellWidth::Continuation => index - 1,
+    };
+
+    for cell_index in [index, other_half_index] {
+        let cell: &mut Cell = &mut buf.cells[cell_index];
+        cell.ch = ' ';
+        cell.combining.clear();
+        cell.width = CellWidth::Single;
+    }
 }
 
 pub fn compose_buffer(buf: &mut ScreenBuffer, draw_calls: &[DrawCall]) {
-    for dc in draw_calls {
-        let mut x: u16 = dc.x;
+    // Stable, calls within a layer keep their push order
+    let mut layered_calls: Vec<&DrawCall> = draw_calls.iter().collect();
+    layered_calls.sort_by_key(|dc| dc.layer);
+
+    for dc in layered_calls {
         let y: u16 = dc.y;
+        let mut x: u16 = dc.x;
 
-        for new_char in dc.rich_text.text.chars() {
+        for (new_char, combining, mut char_width) in layout_graphemes(&dc.rich_text.text) {
             if x >= buf.width || y >= buf.height {
                 break;
             }
 
-            let cell: &mut Cell = &mut buf.cells[(y * buf.width + x) as usize];
             let new_rich_text: &RichText = &dc.rich_text;
-
-            let is_old_char_visible: bool = cell.ch != ' ' && cell.fg != 0;
             let new_char_should_override_old: bool = new_rich_text.fg.a == 1.0;
-            let preserve_old_bg: bool = new_rich_text.bg.a == 0.0;
-            let skip_fg_blending: bool = dc.rich_text.fg.a == 1.0 || dc.rich_text.fg.a == 0.0;
-            let skip_bg_blending: bool = dc.rich_text.bg.a == 1.0 || dc.rich_text.bg.a == 0.0;
+            let index: usize = (y * buf.width + x) as usize;
+
+            // A wide glyph cut off by the right edge is drawn as a blank
+            let (new_char, combining): (char, String) = if x + char_width > buf.width {
+                char_width = 1;
+                (' ', String::new())
+            } else {
+                (new_char, combining)
+            };
 
             if new_char_should_override_old {
-                cell.ch = new_char;
-                cell.bold = new_rich_text.bold;
-
-                if skip_fg_blending {
-                    cell.fg = rgba_to_packed_rgb(&new_rich_text.fg);
-                } else {
-                    let bottom_color: Rgba = if is_old_char_visible {
-                        packed_rgb_to_rgba(cell.fg)
-                    } else {
-                        packed_rgb_to_rgba(cell.bg)
-                    };
-                    let blended_fg = blend_source_over(&bottom_color, &new_rich_text.fg);
-                    cell.fg = rgba_to_packed_rgb(&blended_fg);
+                for cell_index in index..index + char_width as usize {
+                    break_wide_glyph(buf, cell_index);
                 }
-            } else if !skip_bg_blending {
-                // Special case for no new char but new blended bg => tint the old fg
-                let old_fg: Rgba = packed_rgb_to_rgba(cell.fg);
-                cell.fg = rgba_to_packed_rgb(&blend_source_over(&old_fg, &new_rich_text.bg))
             }
 
-            if !preserve_old_bg {
-                if skip_bg_blending {
-                    cell.bg = rgba_to_packed_rgb(&new_rich_text.bg);
-                } else {
-                    let old_bg: Rgba = packed_rgb_to_rgba(cell.bg);
-                    let blended_bg: Rgba = blend_source_over(&old_bg, &new_rich_text.bg);
-                    cell.bg = rgba_to_packed_rgb(&blended_bg);
-                }
+            let new_cell_width: CellWidth = if char_width == 2 {
+                CellWidth::Wide
+            } else {
+                CellWidth::Single
+            };
+            compose_cell(
+                &mut buf.cells[index],
+                new_char,
+                combining,
+                new_cell_width,
+                new_rich_text,
+            );
+
+            if char_width == 2 {
+                compose_cell(
+                    &mut buf.cells[index + 1],
+                    ' ',
+                    String::new(),
+                    CellWidth::Continuation,
+                    new_rich_text,
+                );
             }
 
-            x += 1;
+            x += char_width;
+        }
+    }
+}
+
+fn compose_cell(
+    cell: &mut Cell,
+    new_char: char,
+    combining: String,
+    width: CellWidth,
+    new_rich_text: &RichText,
+) {
+    let is_old_char_visible: bool = cell.ch != ' ' && cell.fg != 0;
+    let new_char_should_override_old: bool = new_rich_text.fg.a == 1.0;
+    let preserve_old_bg: bool = new_rich_text.bg.a == 0.0;
+    let skip_fg_blending: bool = new_rich_text.fg.a == 1.0 || new_rich_text.fg.a == 0.0;
+    let skip_bg_blending: bool = new_rich_text.bg.a == 1.0 || new_rich_text.bg.a == 0.0;
+
+    if new_char_should_override_old {
+        cell.ch = new_char;
+        cell.combining = combining;
+        cell.width = width;
+        cell.bold = new_rich_text.bold;
+        cell.attributes = new_rich_text.attributes;
+        cell.underline_color = new_rich_text
+            .underline_color
+            .as_ref()
+            .map(rgba_to_packed_rgb);
+
+        if skip_fg_blending {
+            cell.fg = rgba_to_packed_rgb(&new_rich_text.fg);
+        } else {
+            let bottom_color: Rgba = if is_old_char_visible {
+                packed_rgb_to_rgba(cell.fg)
+            } else {
+                packed_rgb_to_rgba(cell.bg)
+            };
+            let blended_fg = blend_source_over(&bottom_color, &new_rich_text.fg);
+            cell.fg = rgba_to_packed_rgb(&blended_fg);
+        }
+    } else if !skip_bg_blending {
+        // Special case for no new char but new blended bg => tint the old fg
+        let old_fg: Rgba = packed_rgb_to_rgba(cell.fg);
+        cell.fg = rgba_to_packed_rgb(&blend_source_over(&old_fg, &new_rich_text.bg))
+    }
+
+    if !preserve_old_bg {
+        if skip_bg_blending {
+            cell.bg = rgba_to_packed_rgb(&new_rich_text.bg);
+        } else {
+            let old_bg: Rgba = packed_rgb_to_rgba(cell.bg);
+            let blended_bg: Rgba = blend_source_over(&old_bg, &new_rich_text.bg);
+            cell.bg = rgba_to_packed_rgb(&blended_bg);
         }
     }
 }
@@ -299,14 +610,23 @@ pub fn compose_buffer(buf: &mut ScreenBuffer, draw_calls: &[DrawCall]) {
 pub fn fill_screen_background(buf: &mut ScreenBuffer, bg: (u8, u8, u8)) {
     for cell in buf.cells.iter_mut() {
         cell.ch = ' ';
+        cell.combining.clear();
+        cell.width = CellWidth::Single;
         cell.fg = 0x000000; // PackedRGB Black
         cell.bg = pack_rgb(bg.0, bg.1, bg.2);
         cell.bold = false;
+        cell.attributes = TextAttributes::default();
+        cell.underline_color = None;
     }
 }
 
 pub fn draw_text(draw_queue: &mut Vec<DrawCall>, x: u16, y: u16, rich_text: RichText) {
-    draw_queue.push(DrawCall { x, y, rich_text });
+    draw_queue.push(DrawCall {
+        x,
+        y,
+        rich_text,
+        layer: Layer::default(),
+    });
 }
 
 pub fn draw_rect(draw_queue: &mut Vec<DrawCall>, x: i16, y: i16, w: u16, h: u16, color: Rgba) {
@@ -340,6 +660,7 @@ pub fn draw_rect(draw_queue: &mut Vec<DrawCall>, x: i16, y: i16, w: u16, h: u16,
                 // This ensures the old buf char is drawn
                 .with_fg(Rgba::from_u8(0, 0, 0, 0.0))
                 .with_bg(color),
+            layer: Layer::default(),
         })
     }
 }
@@ -389,31 +710,45 @@ pub fn blend_source_over(bottom: &Rgba, top: &Rgba) -> Rgba {
     Rgba::from_f32(out_r, out_g, out_b, out_a)
 }
 
-pub fn build_crossterm_content_style(cell: &Cell) -> ContentStyle {
-    let (fg_r, fg_g, fg_b) = unpack_rgb(cell.fg);
-    let (bg_r, bg_g, bg_b) = unpack_rgb(cell.bg);
+fn crossterm_attribute(flag: TextAttributes) -> Attribute {
+    match flag {
+        TextAttributes::ITALIC => Attribute::Italic,
+        TextAttributes::UNDERLINE => Attribute::Underlined,
+        TextAttributes::DIM => Attribute::Dim,
+        TextAttributes::REVERSE => Attribute::Reverse,
+        TextAttributes::BLINK => Attribute::SlowBlink,
+        TextAttributes::STRIKETHROUGH => Attribute::CrossedOut,
+        _ => unreachable!("Only single flags are mapped"),
+    }
+}
 
-    let fg_color: Color = Color::Rgb {
-        r: fg_r,
-        g: fg_g,
-        b: fg_b,
-    };
+/// Style of a cell at the terminal's color depth. Only the bg is dithered, dithered text
+/// would be hard to read.
+pub fn build_crossterm_content_style(
+    cell: &Cell,
+    color_depth: ColorDepth,
+    bg_dither: f32,
+) -> ContentStyle {
+    let fg_color: Color = quantize_color(unpack_rgb(cell.fg), color_depth, 0.0);
+    let bg_color: Color = quantize_color(unpack_rgb(cell.bg), color_depth, bg_dither);
 
-    let bg_color: Color = Color::Rgb {
-        r: bg_r,
-        g: bg_g,
-        b: bg_b,
-    };
+    let underline_color: Option<Color> = cell
+        .underline_color
+        .map(|color| quantize_color(unpack_rgb(color), color_depth, 0.0));
 
     let mut attrs: Attributes = Attributes::none();
     if cell.bold {
         attrs = attrs.with(Attribute::Bold);
     }
 
+    for flag in TextAttributes::iter().filter(|flag| cell.attributes.contains(*flag)) {
+        attrs = attrs.with(crossterm_attribute(flag));
+    }
+
     ContentStyle {
         foreground_color: Some(fg_color),
         background_color: Some(bg_color),
-        underline_color: None,
+        underline_color,
         attributes: attrs,
     }
 }
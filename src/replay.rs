@@ -0,0 +1,220 @@
+use std::{
+    collections::VecDeque,
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+
+use crate::save::{decode_value, decode_variant, invalid_data};
+
+/// Bumped whenever the replay format changes, older replays are refused instead of misread.
+pub const REPLAY_VERSION: u32 = 1;
+const REPLAY_HEADER: &str = "term-slots-replay";
+
+/// Everything one `tick` consumed, which together with the seed is enough to replay it.
+pub struct ReplayFrame {
+    pub dt: f32,
+    pub events: Vec<Event>,
+}
+
+pub struct Replay {
+    pub seed: u64,
+    pub frames: VecDeque<ReplayFrame>,
+}
+
+/// Frames are streamed to the file as they happen, so a crash still leaves a usable replay.
+pub struct ReplayRecorder {
+    writer: BufWriter<File>,
+}
+
+pub fn start_recording(path: &Path, seed: u64) -> io::Result<ReplayRecorder> {
+    let mut writer: BufWriter<File> = BufWriter::new(File::create(path)?);
+    writeln!(writer, "{REPLAY_HEADER} {REPLAY_VERSION}")?;
+    writeln!(writer, "seed {seed}")?;
+
+    Ok(ReplayRecorder { writer })
+}
+
+/// One `frame dt events...` line per tick, `dt` round-trips exactly through its text form.
+pub fn record_frame(recorder: &mut ReplayRecorder, dt: f32, events: &[Event]) -> io::Result<()> {
+    let mut line: String = format!("frame {dt}");
+    for token in events.iter().filter_map(encode_event) {
+        line.push(' ');
+        line.push_str(&token);
+    }
+
+    writeln!(recorder.writer, "{line}")
+}
+
+pub fn finish_recording(mut recorder: ReplayRecorder) -> io::Result<()> {
+    recorder.writer.flush()
+}
+
+pub fn load_replay(path: &Path) -> io::Result<Replay> {
+    let text: String = fs::read_to_string(path)?;
+    let mut lines = text.lines();
+
+    let header: String = lines.next().unwrap_or_default().to_string();
+    if header != format!("{REPLAY_HEADER} {REPLAY_VERSION}") {
+        return Err(invalid_data(format!("Unsupported replay `{header}`")));
+    }
+
+    let mut replay: Replay = Replay {
+        seed: 0,
+        frames: VecDeque::new(),
+    };
+
+    for line in lines {
+        let mut values = line.split_whitespace();
+        match values.next() {
+            Some("seed") => replay.seed = decode_value(values.next())?,
+            Some("frame") => replay.frames.push_back(ReplayFrame {
+                dt: decode_value(values.next())?,
+                events: values
+                    .map(decode_event)
+                    .collect::<io::Result<Vec<Event>>>()?,
+            }),
+            Some(key) => return Err(invalid_data(format!("Unknown replay entry `{key}`"))),
+            None => {}
+        }
+    }
+
+    Ok(replay)
+}
+
+pub fn next_replay_frame(replay: &mut Replay) -> Option<ReplayFrame> {
+    replay.frames.pop_front()
+}
+
+fn mouse_event_kinds() -> Vec<MouseEventKind> {
+    let mut kinds: Vec<MouseEventKind> = vec![
+        MouseEventKind::Moved,
+        MouseEventKind::ScrollDown,
+        MouseEventKind::ScrollUp,
+        MouseEventKind::ScrollLeft,
+        MouseEventKind::ScrollRight,
+    ];
+
+    for button in [MouseButton::Left, MouseButton::Right, MouseButton::Middle] {
+        kinds.push(MouseEventKind::Down(button));
+        kinds.push(MouseEventKind::Up(button));
+        kinds.push(MouseEventKind::Drag(button));
+    }
+
+    kinds
+}
+
+/// Key codes without data, `Char` and `F` keys are encoded separately.
+fn plain_key_codes() -> [KeyCode; 23] {
+    [
+        KeyCode::Backspace,
+        KeyCode::Enter,
+        KeyCode::Left,
+        KeyCode::Right,
+        KeyCode::Up,
+        KeyCode::Down,
+        KeyCode::Home,
+        KeyCode::End,
+        KeyCode::PageUp,
+        KeyCode::PageDown,
+        KeyCode::Tab,
+        KeyCode::BackTab,
+        KeyCode::Delete,
+        KeyCode::Insert,
+        KeyCode::Null,
+        KeyCode::Esc,
+        KeyCode::CapsLock,
+        KeyCode::ScrollLock,
+        KeyCode::NumLock,
+        KeyCode::PrintScreen,
+        KeyCode::Pause,
+        KeyCode::Menu,
+        KeyCode::KeypadBegin,
+    ]
+}
+
+/// Events are `kind:fields...` tokens. Pastes and media/modifier keys are skipped,
+/// `resolve_input` ignores them anyway.
+fn encode_event(event: &Event) -> Option<String> {
+    match event {
+        Event::Key(key_event) => {
+            let code: String = match key_event.code {
+                KeyCode::Char(char) => format!("c{}", char as u32),
+                KeyCode::F(number) => format!("f{number}"),
+                code if plain_key_codes().contains(&code) => format!("{code:?}"),
+                _ => return None,
+            };
+
+            Some(format!(
+                "key:{code}:{:?}:{}",
+                key_event.kind,
+                key_event.modifiers.bits()
+            ))
+        }
+        Event::Mouse(mouse_event) => Some(format!(
+            "mouse:{:?}:{}:{}:{}",
+            mouse_event.kind,
+            mouse_event.column,
+            mouse_event.row,
+            mouse_event.modifiers.bits()
+        )),
+        Event::Resize(width, height) => Some(format!("resize:{width}:{height}")),
+        Event::FocusGained => Some("focus:gained".to_string()),
+        Event::FocusLost => Some("focus:lost".to_string()),
+        Event::Paste(_) => None,
+    }
+}
+
+fn decode_event(token: &str) -> io::Result<Event> {
+    let parts: Vec<&str> = token.split(':').collect();
+
+    match parts.as_slice() {
+        ["key", code, kind, modifiers] => {
+            let code: KeyCode = if let Some(char) = code.strip_prefix('c') {
+                let char: u32 = decode_value(Some(char))?;
+                KeyCode::Char(char::from_u32(char).ok_or_else(|| invalid_data("Invalid key char"))?)
+            } else if let Some(number) = code.strip_prefix('f') {
+                KeyCode::F(decode_value(Some(number))?)
+            } else {
+                decode_variant(code, plain_key_codes())?
+            };
+
+            let kind: KeyEventKind = decode_variant(
+                kind,
+                [
+                    KeyEventKind::Press,
+                    KeyEventKind::Repeat,
+                    KeyEventKind::Release,
+                ],
+            )?;
+
+            Ok(Event::Key(KeyEvent::new_with_kind(
+                code,
+                decode_modifiers(modifiers)?,
+                kind,
+            )))
+        }
+        ["mouse", kind, column, row, modifiers] => Ok(Event::Mouse(MouseEvent {
+            kind: decode_variant(kind, mouse_event_kinds())?,
+            column: decode_value(Some(column))?,
+            row: decode_value(Some(row))?,
+            modifiers: decode_modifiers(modifiers)?,
+        })),
+        ["resize", width, height] => Ok(Event::Resize(
+            decode_value(Some(width))?,
+            decode_value(Some(height))?,
+        )),
+        ["focus", "gained"] => Ok(Event::FocusGained),
+        ["focus", "lost"] => Ok(Event::FocusLost),
+        _ => Err(invalid_data(format!("Invalid replay event `{token}`"))),
+    }
+}
+
+fn decode_modifiers(token: &str) -> io::Result<KeyModifiers> {
+    KeyModifiers::from_bits(decode_value(Some(token))?)
+        .ok_or_else(|| invalid_data(format!("Invalid key modifiers `{token}`")))
+}
@@ -0,0 +1,54 @@
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+
+use crate::{
+    TERM_SCREEN_HEIGHT, TERM_SCREEN_WIDTH,
+    constants::SIDEBAR_BORDER_X,
+    context::Context,
+    renderer::{DrawCall, Rgba, RichText, draw_text},
+    utils::center_text_unicode,
+};
+
+/// The one RNG every random decision in a run goes through, so a seed reproduces a game.
+pub type GameRng = ChaCha12Rng;
+
+/// Seed for runs started without `--seed`, kept short enough to read off the screen.
+pub fn random_seed() -> u64 {
+    rand::random::<u32>() as u64
+}
+
+/// Numbers are used as is, any other text is hashed (FNV-1a) so words work as seeds too.
+pub fn parse_seed(value: &str) -> u64 {
+    if let Ok(seed) = value.parse::<u64>() {
+        return seed;
+    }
+
+    value.bytes().fold(0xcbf29ce484222325, |hash: u64, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+pub fn build_rng(seed: u64) -> GameRng {
+    GameRng::seed_from_u64(seed)
+}
+
+pub fn reseed(ctx: &mut Context, seed: u64) {
+    ctx.seed = seed;
+    ctx.rng = build_rng(seed);
+}
+
+pub fn draw_seed(draw_queue: &mut Vec<DrawCall>, ctx: &Context) {
+    let sidebar_width: usize = (TERM_SCREEN_WIDTH - SIDEBAR_BORDER_X - 1) as usize;
+    let mut text: String = format!("seed {}", ctx.seed);
+    if text.len() > sidebar_width {
+        text = ctx.seed.to_string();
+    }
+
+    draw_text(
+        draw_queue,
+        SIDEBAR_BORDER_X + 1,
+        TERM_SCREEN_HEIGHT - 1,
+        RichText::new(center_text_unicode(text, sidebar_width))
+            .with_fg(Rgba::from_u8(120, 120, 120, 1.0)),
+    );
+}
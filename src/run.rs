@@ -0,0 +1,219 @@
+use crate::{
+    TERM_SCREEN_WIDTH,
+    constants::SIDEBAR_BORDER_X,
+    context::Context,
+    renderer::{DrawCall, Rgba, RichText, draw_rect, draw_text},
+    shop::restock_shop,
+    slot_machine::{SlotMachineState, shuffle_slot_machine, spin_cost},
+    utils::center_text_unicode,
+};
+
+/// Clearing the target of this round wins the run.
+pub const FINAL_ROUND: i32 = 8;
+/// Spins available each round before the round has to be cleared with the cards at hand.
+pub const ROUND_SPIN_BUDGET: i32 = 8;
+
+pub const RUN_OVER_PANEL_X: u16 = 5;
+pub const RUN_OVER_PANEL_Y: u16 = 10;
+pub const RUN_OVER_PANEL_WIDTH: u16 = 27;
+pub const RUN_OVER_PANEL_HEIGHT: u16 = 8;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RunPhase {
+    Playing,
+    Shop,
+    GameOver,
+    Victory,
+}
+
+pub struct Run {
+    pub phase: RunPhase,
+    pub round: i32,
+    /// `SlotMachine.spin_count` at the start of the current round,
+    /// used to derive how much of the spin budget is left.
+    pub round_start_spin_count: i32,
+    /// Extra spins bought in the shop for the upcoming round.
+    pub bonus_spins: i32,
+}
+
+impl Default for Run {
+    fn default() -> Self {
+        Self {
+            phase: RunPhase::Playing,
+            round: 1,
+            round_start_spin_count: 0,
+            bonus_spins: 0,
+        }
+    }
+}
+
+/// Score targets are cumulative, `Context.score` has to reach it to clear the round.
+pub fn round_score_target(round: i32) -> i32 {
+    let base_target: i32 = 150;
+    base_target * round + 25 * round * (round - 1)
+}
+
+pub fn run_is_active(ctx: &Context) -> bool {
+    matches!(ctx.run.phase, RunPhase::Playing)
+}
+
+pub fn spins_left(ctx: &Context) -> i32 {
+    let spins_used: i32 = ctx.slot_machine.spin_count - ctx.run.round_start_spin_count;
+    (ROUND_SPIN_BUDGET + ctx.run.bonus_spins - spins_used).max(0)
+}
+
+pub fn can_afford_spin(ctx: &Context) -> bool {
+    spins_left(ctx) > 0 && ctx.coins >= spin_cost(ctx.slot_machine.spin_count)
+}
+
+/// Advances rounds and detects the end of the run, called once per tick after input.
+pub fn update_run(ctx: &mut Context) {
+    if !run_is_active(ctx) {
+        return;
+    }
+
+    if ctx.score >= round_score_target(ctx.run.round) {
+        if ctx.run.round >= FINAL_ROUND {
+            ctx.run.phase = RunPhase::Victory;
+            return;
+        }
+
+        ctx.run.round += 1;
+        ctx.run.bonus_spins = 0;
+        ctx.run.phase = RunPhase::Shop;
+        ctx.luck_armed = false;
+        ctx.shop.reroll_count = 0;
+        restock_shop(ctx);
+        return;
+    }
+
+    let slots_idle: bool = matches!(ctx.slot_machine.state, SlotMachineState::Idle);
+    let no_cards_left: bool = ctx
+        .hand_card_slots
+        .iter()
+        .chain(ctx.table_card_slots.iter())
+        .all(|slot| slot.card.is_none());
+
+    if slots_idle && no_cards_left && !can_afford_spin(ctx) {
+        ctx.run.phase = RunPhase::GameOver;
+    }
+}
+
+/// Starts the round the shop was opened for.
+pub fn leave_shop(ctx: &mut Context) {
+    ctx.reel_editor = None;
+    ctx.run.phase = RunPhase::Playing;
+    ctx.run.round_start_spin_count = ctx.slot_machine.spin_count;
+}
+
+/// Resets everything run related while keeping the session (settings, screen, timers) intact.
+pub fn start_new_run(ctx: &mut Context) {
+    let fresh: Context = Context::default();
+
+    ctx.score = fresh.score;
+    ctx.coins = fresh.coins;
+    ctx.luck = fresh.luck;
+    ctx.luck_armed = fresh.luck_armed;
+    ctx.dry_spin_streak = fresh.dry_spin_streak;
+    ctx.poker_hand = fresh.poker_hand;
+    ctx.hand_levels = fresh.hand_levels;
+    ctx.hand_play_counts = fresh.hand_play_counts;
+    ctx.scoring = fresh.scoring;
+    ctx.table_card_slots = fresh.table_card_slots;
+    ctx.hand_card_slots = fresh.hand_card_slots;
+    ctx.slot_machine = fresh.slot_machine;
+    ctx.run = fresh.run;
+    ctx.relics = fresh.relics;
+    ctx.shop = fresh.shop;
+    ctx.reel_editor = fresh.reel_editor;
+    ctx.hint = fresh.hint;
+    ctx.spin_odds = fresh.spin_odds;
+    ctx.impulse_timestamps.clear();
+
+    shuffle_slot_machine(&mut ctx.slot_machine, &mut ctx.rng);
+}
+
+pub fn draw_run_over_panel(draw_queue: &mut Vec<DrawCall>, ctx: &Context) {
+    let (title, title_color): (&str, Rgba) = match ctx.run.phase {
+        RunPhase::Playing | RunPhase::Shop => return,
+        RunPhase::GameOver => ("GAME OVER", Rgba::from_u8(255, 110, 90, 1.0)),
+        RunPhase::Victory => ("VICTORY!", Rgba::from_u8(255, 215, 90, 1.0)),
+    };
+
+    draw_rect(
+        draw_queue,
+        RUN_OVER_PANEL_X as i16,
+        RUN_OVER_PANEL_Y as i16,
+        RUN_OVER_PANEL_WIDTH,
+        RUN_OVER_PANEL_HEIGHT,
+        Rgba::from_u8(20, 10, 10, 0.9),
+    );
+
+    let width: usize = RUN_OVER_PANEL_WIDTH as usize;
+    let lines: [(String, Rgba, bool); 3] = [
+        (title.to_string(), title_color, true),
+        (
+            format!("Round {}/{FINAL_ROUND}", ctx.run.round),
+            Rgba::from_u8(220, 220, 220, 1.0),
+            false,
+        ),
+        (
+            format!("Score {}", ctx.score),
+            Rgba::from_u8(190, 230, 255, 1.0),
+            false,
+        ),
+    ];
+
+    for (line_index, (text, color, bold)) in lines.into_iter().enumerate() {
+        draw_text(
+            draw_queue,
+            RUN_OVER_PANEL_X,
+            RUN_OVER_PANEL_Y + 1 + line_index as u16,
+            RichText::new(center_text_unicode(text, width))
+                .with_fg(color)
+                .with_bold(bold),
+        );
+    }
+}
+
+/// Round progress shown at the top of the sidebar.
+pub fn draw_round_info(draw_queue: &mut Vec<DrawCall>, ctx: &Context) {
+    let sidebar_inner_width: usize = (TERM_SCREEN_WIDTH - SIDEBAR_BORDER_X - 1) as usize;
+
+    draw_text(
+        draw_queue,
+        SIDEBAR_BORDER_X + 1,
+        1,
+        RichText::new(center_text_unicode(
+            format!("ROUND {}/{FINAL_ROUND}", ctx.run.round),
+            sidebar_inner_width,
+        ))
+        .with_fg(Rgba::from_u8(255, 200, 160, 1.0))
+        .with_bold(true),
+    );
+
+    draw_text(
+        draw_queue,
+        SIDEBAR_BORDER_X + 3,
+        4,
+        RichText::new(format!(
+            "{:>12}",
+            format!("/ {}", round_score_target(ctx.run.round))
+        ))
+        .with_fg(Rgba::from_u8(120, 150, 170, 1.0)),
+    );
+
+    let spins_left: i32 = spins_left(ctx);
+    let spins_left_color: Rgba = if spins_left > 0 {
+        Rgba::from_u8(200, 180, 160, 1.0)
+    } else {
+        Rgba::from_u8(255, 110, 90, 1.0)
+    };
+    draw_text(
+        draw_queue,
+        SIDEBAR_BORDER_X + 3,
+        10,
+        RichText::new(center_text_unicode(format!("{spins_left} spins left"), 12))
+            .with_fg(spins_left_color),
+    );
+}
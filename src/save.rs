@@ -0,0 +1,641 @@
+use std::{
+    collections::HashMap,
+    env,
+    fmt::Debug,
+    fs,
+    io::{self, ErrorKind},
+    path::PathBuf,
+    str::{FromStr, SplitWhitespace},
+};
+
+use crate::{
+    button::{Button, ButtonId},
+    card::{Card, Enhancement, Rank, Suit, WildKind},
+    card_slot::{CardSlot, build_card_slots},
+    constants::SIDEBAR_BORDER_X,
+    context::{Context, ImpulseId},
+    hand::{HAND_CARD_X_SPACING, HAND_ORIGIN_X, HAND_ORIGIN_Y, MAX_HAND_SLOT_COUNT},
+    poker_hand::{PokerHand, update_current_poker_hand},
+    reel_editor::ReelEditor,
+    relic::Relic,
+    renderer::{DrawCall, Rgba, RichText, draw_rect, draw_text},
+    rng::reseed,
+    run::{
+        RUN_OVER_PANEL_HEIGHT, RUN_OVER_PANEL_WIDTH, RUN_OVER_PANEL_X, RUN_OVER_PANEL_Y, RunPhase,
+    },
+    shop::{Consumable, ReelEdit, ShopItem},
+    slot_machine::{ReelCard, SlotMachineColumn, SlotMachineState, check_reel_limits},
+    table::{TABLE_CARD_X_SPACING, TABLE_ORIGIN_X, TABLE_ORIGIN_Y, TABLE_SLOT_COUNT},
+    utils::center_text_unicode,
+};
+
+/// Bumped whenever the save format changes, older saves are refused instead of misread.
+pub const SAVE_VERSION: u32 = 3;
+const SAVE_HEADER: &str = "term-slots-save";
+const SAVE_FILE_NAME: &str = "save.txt";
+/// How long the saved/failed notice stays on screen.
+const SAVE_NOTICE_DURATION_SEC: f32 = 1.5;
+
+/// `$XDG_DATA_HOME/term-slots`, falling back to `~/.local/share/term-slots`,
+/// or `%APPDATA%\term-slots` on Windows.
+fn save_dir() -> Option<PathBuf> {
+    let data_dir: PathBuf = if cfg!(windows) {
+        PathBuf::from(env::var_os("APPDATA")?)
+    } else if let Some(xdg_data_home) = env::var_os("XDG_DATA_HOME").filter(|dir| !dir.is_empty()) {
+        PathBuf::from(xdg_data_home)
+    } else {
+        PathBuf::from(env::var_os("HOME")?)
+            .join(".local")
+            .join("share")
+    };
+
+    Some(data_dir.join("term-slots"))
+}
+
+fn save_file_path() -> io::Result<PathBuf> {
+    save_dir()
+        .map(|dir| dir.join(SAVE_FILE_NAME))
+        .ok_or_else(|| io::Error::new(ErrorKind::NotFound, "No user data directory"))
+}
+
+pub fn save_exists() -> bool {
+    save_file_path().is_ok_and(|path| path.is_file())
+}
+
+pub fn write_save(ctx: &Context) -> io::Result<()> {
+    let path: PathBuf = save_file_path()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    // Written next to the old save first, so a failed write can't corrupt it
+    let temp_path: PathBuf = path.with_extension("tmp");
+    fs::write(&temp_path, encode_save(ctx))?;
+    fs::rename(&temp_path, &path)
+}
+
+/// Loads the save into a fresh `Context`, which rebuilds everything that isn't saved.
+pub fn load_save() -> io::Result<Context> {
+    let text: String = fs::read_to_string(save_file_path()?)?;
+    decode_save(&text)
+}
+
+pub fn delete_save() -> io::Result<()> {
+    match fs::remove_file(save_file_path()?) {
+        Err(error) if error.kind() != ErrorKind::NotFound => Err(error),
+        _ => Ok(()),
+    }
+}
+
+/// Saves on demand, flashing a notice with the outcome. Ignored while the resume prompt is
+/// open, the save it offers would be lost, and in sessions that leave the save alone.
+pub fn save_game(ctx: &mut Context) {
+    if ctx.resume_prompt_open || ctx.save_disabled {
+        return;
+    }
+
+    let impulse_id: ImpulseId = match write_save(ctx) {
+        Ok(()) => ImpulseId::GameSaved,
+        Err(_) => ImpulseId::SaveFailed,
+    };
+    ctx.impulse_timestamps.insert(impulse_id, ctx.game_time);
+}
+
+/// Saves the run on exit, a finished run has nothing left to resume so its save is removed.
+pub fn save_on_exit(ctx: &Context) -> io::Result<()> {
+    match ctx.run.phase {
+        RunPhase::GameOver | RunPhase::Victory => delete_save(),
+        RunPhase::Playing | RunPhase::Shop => write_save(ctx),
+    }
+}
+
+// --- Encoding ---
+
+fn encode_card(card: &Card) -> String {
+    format!(
+        "{:?}/{:?}/{}/{}",
+        card.suit,
+        card.rank,
+        encode_option(card.enhancement),
+        encode_option(card.wild),
+    )
+}
+
+fn encode_option<T: Debug>(maybe_value: Option<T>) -> String {
+    maybe_value.map_or("-".to_string(), |value| format!("{value:?}"))
+}
+
+fn encode_card_slots(card_slots: &[CardSlot]) -> String {
+    card_slots
+        .iter()
+        .map(|slot| slot.card.as_ref().map_or("-".to_string(), encode_card))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn encode_counts(counts: &HashMap<PokerHand, i32>) -> String {
+    // Sorted so the same state always saves the same way
+    let mut entries: Vec<(&PokerHand, &i32)> = counts.iter().collect();
+    entries.sort();
+    entries
+        .iter()
+        .map(|(poker_hand, count)| format!("{poker_hand:?}:{count}"))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn encode_column(column: &SlotMachineColumn) -> String {
+    let lucky_ranks: String = if column.lucky_ranks.is_empty() {
+        "-".to_string()
+    } else {
+        column
+            .lucky_ranks
+            .iter()
+            .map(|rank| format!("{rank:?}"))
+            .collect::<Vec<String>>()
+            .join(",")
+    };
+
+    let cards: Vec<String> = column
+        .cards
+        .iter()
+        .map(|reel_card| format!("{}/{}", encode_card(&reel_card.card), reel_card.weight))
+        .collect();
+
+    format!(
+        "{} {} {} {} {} {} {}",
+        column.cursor,
+        column.spin_duration,
+        column.spin_time_remaining,
+        column.spin_speed,
+        column.luck_nudge,
+        lucky_ranks,
+        cards.join(" ")
+    )
+}
+
+/// One `key values...` line per part of the state, columns get a line each.
+pub fn encode_save(ctx: &Context) -> String {
+    let mut lines: Vec<String> = vec![
+        format!("{SAVE_HEADER} {SAVE_VERSION}"),
+        format!("score {}", ctx.score),
+        format!("coins {}", ctx.coins),
+        // The stream position keeps a resumed run on the same random sequence
+        format!("rng {} {}", ctx.seed, ctx.rng.get_word_pos()),
+        format!(
+            "luck {} {} {}",
+            ctx.luck, ctx.luck_armed, ctx.dry_spin_streak
+        ),
+        format!(
+            "settings {} {}",
+            ctx.settings.vignette_enabled, ctx.settings.bg_shader_enabled
+        ),
+        format!(
+            "run {:?} {} {} {}",
+            ctx.run.phase, ctx.run.round, ctx.run.round_start_spin_count, ctx.run.bonus_spins
+        ),
+        format!(
+            "slot_machine {:?} {}",
+            ctx.slot_machine.state, ctx.slot_machine.spin_count
+        ),
+        format!("hand {}", encode_card_slots(&ctx.hand_card_slots)),
+        format!("table {}", encode_card_slots(&ctx.table_card_slots)),
+        format!(
+            "relics {}",
+            ctx.relics
+                .iter()
+                .map(|relic| format!("{relic:?}"))
+                .collect::<Vec<String>>()
+                .join(" ")
+        ),
+        format!("hand_levels {}", encode_counts(&ctx.hand_levels)),
+        format!("hand_play_counts {}", encode_counts(&ctx.hand_play_counts)),
+        format!(
+            "shop {} {}",
+            ctx.shop.reroll_count,
+            ctx.shop
+                .stock
+                .iter()
+                .map(|item| encode_option(*item))
+                .collect::<Vec<String>>()
+                .join(" ")
+        ),
+    ];
+
+    if let Some(editor) = &ctx.reel_editor {
+        lines.push(format!(
+            "reel_editor {} {} {}",
+            editor.column_index,
+            encode_option(editor.selected_index),
+            encode_card(&editor.new_card)
+        ));
+    }
+
+    for column in &ctx.slot_machine.columns {
+        lines.push(format!("column {}", encode_column(column)));
+    }
+
+    lines.join("\n") + "\n"
+}
+
+// --- Decoding ---
+
+pub fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, message.into())
+}
+
+/// Enums are saved by their `Debug` name, so parsing looks the name up among every variant.
+pub fn decode_variant<T: Debug>(
+    token: &str,
+    variants: impl IntoIterator<Item = T>,
+) -> io::Result<T> {
+    variants
+        .into_iter()
+        .find(|variant| format!("{variant:?}") == token)
+        .ok_or_else(|| invalid_data(format!("Unknown value `{token}`")))
+}
+
+fn decode_option<T>(token: &str, decode: impl Fn(&str) -> io::Result<T>) -> io::Result<Option<T>> {
+    match token {
+        "-" => Ok(None),
+        _ => decode(token).map(Some),
+    }
+}
+
+/// Numbers and bools, anything saved through its `Display` implementation.
+pub fn decode_value<T: FromStr>(token: Option<&str>) -> io::Result<T> {
+    let token: &str = token.ok_or_else(|| invalid_data("Missing value"))?;
+    token
+        .parse::<T>()
+        .map_err(|_| invalid_data(format!("Invalid value `{token}`")))
+}
+
+fn decode_card(token: &str) -> io::Result<Card> {
+    let parts: Vec<&str> = token.split('/').collect();
+    let [suit, rank, enhancement, wild] = parts[..] else {
+        return Err(invalid_data(format!("Invalid card `{token}`")));
+    };
+
+    Ok(Card {
+        suit: decode_variant(suit, Suit::iter())?,
+        rank: decode_variant(rank, Rank::iter())?,
+        enhancement: decode_option(enhancement, |token| {
+            decode_variant(token, Enhancement::iter())
+        })?,
+        wild: decode_option(wild, |token| decode_variant(token, WildKind::iter()))?,
+    })
+}
+
+fn decode_reel_card(token: &str) -> io::Result<ReelCard> {
+    let (card, weight) = token
+        .rsplit_once('/')
+        .ok_or_else(|| invalid_data(format!("Invalid reel card `{token}`")))?;
+
+    Ok(ReelCard {
+        card: decode_card(card)?,
+        weight: decode_value(Some(weight))?,
+    })
+}
+
+fn decode_card_slots(
+    values: SplitWhitespace,
+    origin_x: u16,
+    origin_y: u16,
+    x_spacing: u16,
+) -> io::Result<Vec<CardSlot>> {
+    let cards: Vec<Option<Card>> = values
+        .map(|token| decode_option(token, decode_card))
+        .collect::<io::Result<Vec<Option<Card>>>>()?;
+
+    let mut card_slots: Vec<CardSlot> =
+        build_card_slots(origin_x, origin_y, x_spacing, cards.len());
+    for (slot, card) in card_slots.iter_mut().zip(cards) {
+        slot.card = card;
+    }
+
+    Ok(card_slots)
+}
+
+fn decode_counts(values: SplitWhitespace) -> io::Result<HashMap<PokerHand, i32>> {
+    values
+        .map(|token| {
+            let (poker_hand, count) = token
+                .split_once(':')
+                .ok_or_else(|| invalid_data(format!("Invalid hand count `{token}`")))?;
+            Ok((
+                decode_variant(poker_hand, PokerHand::iter())?,
+                decode_value(Some(count))?,
+            ))
+        })
+        .collect()
+}
+
+fn all_shop_items() -> Vec<ShopItem> {
+    let mut items: Vec<ShopItem> = vec![ShopItem::HandSlot];
+    items.extend(Relic::iter().map(ShopItem::Relic));
+    items.extend(
+        [ReelEdit::Thin, ReelEdit::AceUp, ReelEdit::Wild]
+            .into_iter()
+            .map(ShopItem::ReelEdit),
+    );
+    items.extend(Enhancement::iter().map(ShopItem::Enhance));
+    items.extend(PokerHand::iter().map(ShopItem::HandUpgrade));
+    items.extend(
+        [Consumable::LuckCharm, Consumable::ExtraSpins]
+            .into_iter()
+            .map(ShopItem::Consumable),
+    );
+    items
+}
+
+fn decode_column(mut values: SplitWhitespace) -> io::Result<SlotMachineColumn> {
+    let cursor: f32 = decode_value(values.next())?;
+    let spin_duration: f32 = decode_value(values.next())?;
+    let spin_time_remaining: f32 = decode_value(values.next())?;
+    let spin_speed: f32 = decode_value(values.next())?;
+    let luck_nudge: i16 = decode_value(values.next())?;
+    let lucky_ranks: Vec<Rank> = match values.next() {
+        None | Some("-") => vec![],
+        Some(token) => token
+            .split(',')
+            .map(|rank| decode_variant(rank, Rank::iter()))
+            .collect::<io::Result<Vec<Rank>>>()?,
+    };
+    let cards: Vec<ReelCard> = values
+        .map(decode_reel_card)
+        .collect::<io::Result<Vec<ReelCard>>>()?;
+
+    check_reel_limits(&cards).map_err(invalid_data)?;
+
+    Ok(SlotMachineColumn {
+        cursor,
+        cards,
+        spin_duration,
+        spin_time_remaining,
+        spin_speed,
+        lucky_ranks,
+        luck_nudge,
+    })
+}
+
+/// Reads a save written by `encode_save` into a fresh `Context`, anything out of range
+/// is refused as `InvalidData`.
+pub fn decode_save(text: &str) -> io::Result<Context> {
+    let mut lines = text.lines();
+    let header: String = lines.next().unwrap_or_default().to_string();
+    if header != format!("{SAVE_HEADER} {SAVE_VERSION}") {
+        return Err(invalid_data(format!("Unsupported save `{header}`")));
+    }
+
+    let mut ctx: Context = Context::default();
+    let mut columns: Vec<SlotMachineColumn> = vec![];
+
+    for line in lines {
+        let mut values: SplitWhitespace = line.split_whitespace();
+        let Some(key) = values.next() else {
+            continue;
+        };
+
+        match key {
+            "score" => ctx.score = decode_value(values.next())?,
+            "coins" => ctx.coins = decode_value(values.next())?,
+            "rng" => {
+                reseed(&mut ctx, decode_value(values.next())?);
+                ctx.rng.set_word_pos(decode_value(values.next())?);
+            }
+            "luck" => {
+                ctx.luck = decode_value(values.next())?;
+                ctx.luck_armed = decode_value(values.next())?;
+                ctx.dry_spin_streak = decode_value(values.next())?;
+            }
+            "settings" => {
+                ctx.settings.vignette_enabled = decode_value(values.next())?;
+                ctx.settings.bg_shader_enabled = decode_value(values.next())?;
+            }
+            "run" => {
+                let phases = [
+                    RunPhase::Playing,
+                    RunPhase::Shop,
+                    RunPhase::GameOver,
+                    RunPhase::Victory,
+                ];
+                ctx.run.phase = decode_variant(values.next().unwrap_or_default(), phases)?;
+                ctx.run.round = decode_value(values.next())?;
+                ctx.run.round_start_spin_count = decode_value(values.next())?;
+                ctx.run.bonus_spins = decode_value(values.next())?;
+            }
+            "slot_machine" => {
+                let states = [
+                    SlotMachineState::Idle,
+                    SlotMachineState::Spinning,
+                    SlotMachineState::PostSpin,
+                ];
+                ctx.slot_machine.state = decode_variant(values.next().unwrap_or_default(), states)?;
+                ctx.slot_machine.spin_count = decode_value(values.next())?;
+            }
+            "hand" => {
+                ctx.hand_card_slots =
+                    decode_card_slots(values, HAND_ORIGIN_X, HAND_ORIGIN_Y, HAND_CARD_X_SPACING)?;
+            }
+            "table" => {
+                ctx.table_card_slots = decode_card_slots(
+                    values,
+                    TABLE_ORIGIN_X,
+                    TABLE_ORIGIN_Y,
+                    TABLE_CARD_X_SPACING,
+                )?;
+            }
+            "relics" => {
+                ctx.relics = values
+                    .map(|token| decode_variant(token, Relic::iter()))
+                    .collect::<io::Result<Vec<Relic>>>()?;
+            }
+            "hand_levels" => ctx.hand_levels = decode_counts(values)?,
+            "hand_play_counts" => ctx.hand_play_counts = decode_counts(values)?,
+            "shop" => {
+                ctx.shop.reroll_count = decode_value(values.next())?;
+                ctx.shop.stock = values
+                    .map(|token| {
+                        decode_option(token, |token| decode_variant(token, all_shop_items()))
+                    })
+                    .collect::<io::Result<Vec<Option<ShopItem>>>>()?;
+            }
+            "reel_editor" => {
+                ctx.reel_editor = Some(ReelEditor {
+                    column_index: decode_value(values.next())?,
+                    selected_index: decode_option(values.next().unwrap_or("-"), |token| {
+                        decode_value(Some(token))
+                    })?,
+                    new_card: decode_card(values.next().unwrap_or_default())?,
+                });
+            }
+            "column" => columns.push(decode_column(values)?),
+            _ => return Err(invalid_data(format!("Unknown save entry `{key}`"))),
+        }
+    }
+
+    let hand_slot_count: usize = ctx.hand_card_slots.len();
+    if hand_slot_count == 0 || hand_slot_count > MAX_HAND_SLOT_COUNT {
+        return Err(invalid_data("Invalid hand slot count"));
+    }
+    if ctx.table_card_slots.len() != TABLE_SLOT_COUNT as usize {
+        return Err(invalid_data("Invalid table slot count"));
+    }
+    if columns.len() != ctx.slot_machine.columns.len() {
+        return Err(invalid_data("Invalid reel count"));
+    }
+    ctx.slot_machine.columns = columns;
+
+    if let Some(editor) = ctx.reel_editor.as_ref() {
+        let Some(column) = ctx.slot_machine.columns.get(editor.column_index) else {
+            return Err(invalid_data("Invalid reel editor column"));
+        };
+        if editor
+            .selected_index
+            .is_some_and(|index| index >= column.cards.len())
+        {
+            return Err(invalid_data("Invalid reel editor selection"));
+        }
+    }
+
+    // Derived from the table, so it's rebuilt instead of saved
+    update_current_poker_hand(&mut ctx);
+
+    Ok(ctx)
+}
+
+// --- Resume prompt ---
+
+pub fn push_resume_prompt_buttons(buttons: &mut Vec<Button>, ctx: &Context) {
+    if !ctx.resume_prompt_open {
+        return;
+    }
+
+    let y: u16 = RUN_OVER_PANEL_Y + RUN_OVER_PANEL_HEIGHT - 2;
+
+    buttons.push(Button {
+        id: ButtonId::Resume,
+        x: RUN_OVER_PANEL_X + 1,
+        y,
+        w: 12,
+        h: 1,
+        text: "RESUME".to_string(),
+        color: Rgba::from_u8(160, 210, 140, 1.0),
+        on_click: Box::new(|ctx: &mut Context| match load_save() {
+            Ok(loaded) => {
+                let game_time: f32 = ctx.game_time;
+                *ctx = loaded;
+                ctx.game_time = game_time;
+            }
+            Err(_) => {
+                ctx.resume_prompt_open = false;
+                ctx.impulse_timestamps
+                    .insert(ImpulseId::SaveFailed, ctx.game_time);
+            }
+        }),
+        enabled_when: Box::new(|_| true),
+        allow_rmb: false,
+    });
+
+    buttons.push(Button {
+        id: ButtonId::DeclineResume,
+        x: RUN_OVER_PANEL_X + RUN_OVER_PANEL_WIDTH - 13,
+        y,
+        w: 12,
+        h: 1,
+        text: "NEW RUN".to_string(),
+        color: Rgba::from_u8(255, 210, 140, 1.0),
+        on_click: Box::new(|ctx: &mut Context| ctx.resume_prompt_open = false),
+        enabled_when: Box::new(|_| true),
+        allow_rmb: false,
+    });
+}
+
+pub fn draw_resume_prompt(draw_queue: &mut Vec<DrawCall>, ctx: &Context) {
+    if !ctx.resume_prompt_open {
+        return;
+    }
+
+    draw_rect(
+        draw_queue,
+        RUN_OVER_PANEL_X as i16,
+        RUN_OVER_PANEL_Y as i16,
+        RUN_OVER_PANEL_WIDTH,
+        RUN_OVER_PANEL_HEIGHT,
+        Rgba::from_u8(20, 10, 10, 0.95),
+    );
+
+    draw_text(
+        draw_queue,
+        RUN_OVER_PANEL_X,
+        RUN_OVER_PANEL_Y + 1,
+        RichText::new(center_text_unicode(
+            "SAVED RUN FOUND".to_string(),
+            RUN_OVER_PANEL_WIDTH as usize,
+        ))
+        .with_fg(Rgba::from_u8(255, 210, 140, 1.0))
+        .with_bold(true),
+    );
+
+    draw_text(
+        draw_queue,
+        RUN_OVER_PANEL_X,
+        RUN_OVER_PANEL_Y + 3,
+        RichText::new(center_text_unicode(
+            "Pick up where you left off?".to_string(),
+            RUN_OVER_PANEL_WIDTH as usize,
+        ))
+        .with_fg(Rgba::from_u8(220, 220, 220, 1.0)),
+    );
+}
+
+/// Flashes the outcome of the last save or screenshot at the bottom of the sidebar.
+pub fn draw_save_notice(draw_queue: &mut Vec<DrawCall>, ctx: &Context) {
+    let notices: [(ImpulseId, &str, Rgba); 4] = [
+        (
+            ImpulseId::GameSaved,
+            "SAVED",
+            Rgba::from_u8(160, 210, 140, 1.0),
+        ),
+        (
+            ImpulseId::SaveFailed,
+            "SAVE ERROR",
+            Rgba::from_u8(255, 110, 90, 1.0),
+        ),
+        (
+            ImpulseId::ScreenshotSaved,
+            "SCREENSHOT SAVED",
+            Rgba::from_u8(160, 210, 140, 1.0),
+        ),
+        (
+            ImpulseId::ScreenshotFailed,
+            "SCREENSHOT ERROR",
+            Rgba::from_u8(255, 110, 90, 1.0),
+        ),
+    ];
+
+    for (impulse_id, text, color) in notices {
+        let Some(timestamp) = ctx.impulse_timestamps.get(&impulse_id) else {
+            continue;
+        };
+
+        let age: f32 = ctx.game_time - timestamp;
+        if age > SAVE_NOTICE_DURATION_SEC {
+            continue;
+        }
+
+        // Only opaque text gets drawn, so the notice fades into the sidebar color instead
+        let sidebar_color: Rgba = Rgba::from_u8(37, 16, 16, 1.0);
+        let mut fg: Rgba = color.lerp(sidebar_color, (age / SAVE_NOTICE_DURATION_SEC).powi(3));
+        fg.a = 1.0;
+
+        draw_text(
+            draw_queue,
+            SIDEBAR_BORDER_X + 1,
+            28,
+            RichText::new(center_text_unicode(text.to_string(), 16))
+                .with_fg(fg)
+                .with_bold(true),
+        );
+    }
+}
@@ -0,0 +1,487 @@
+use rand::Rng;
+
+use crate::{
+    TERM_SCREEN_WIDTH,
+    card::{Card, CardPlacement, Enhancement},
+    constants::SIDEBAR_BORDER_X,
+    context::Context,
+    hand::{HAND_CARD_X_SPACING, HAND_ORIGIN_X, HAND_ORIGIN_Y},
+    poker_hand::{
+        PokerHand, eval_poker_hand, hand_level, hand_payout, record_played_hand,
+        update_current_poker_hand,
+    },
+    relic::{RELIC_TILE_WIDTH, RELICS_ORIGIN_X, RELICS_ORIGIN_Y},
+    renderer::{DrawCall, Rgba, RichText, draw_text},
+    slot_machine::MIN_REEL_SIZE,
+    table::{TABLE_CARD_X_SPACING, TABLE_ORIGIN_X, TABLE_ORIGIN_Y},
+};
+
+/// Chance of a scoring glass card breaking, which removes it from the reels for good.
+pub const GLASS_BREAK_CHANCE: f64 = 0.25;
+/// Time between two revealed steps of a score breakdown.
+pub const SCORE_STEP_DURATION_SEC: f32 = 0.3;
+/// Time the final total stays up before it's paid out.
+pub const SCORE_TOTAL_HOLD_SEC: f32 = 0.8;
+
+pub const CHIPS_COLOR: Rgba = Rgba {
+    r: 120,
+    g: 190,
+    b: 255,
+    a: 1.0,
+};
+
+pub const MULT_COLOR: Rgba = Rgba {
+    r: 255,
+    g: 110,
+    b: 90,
+    a: 1.0,
+};
+
+pub const COINS_COLOR: Rgba = Rgba {
+    r: 255,
+    g: 255,
+    b: 155,
+    a: 1.0,
+};
+
+/// What a step of a score breakdown comes from, used to point at it while animating.
+#[derive(Clone, Copy, PartialEq)]
+pub enum ScoreSource {
+    PokerHand,
+    Table(usize),
+    Hand(usize),
+    Relic(usize),
+}
+
+pub struct ScoreStep {
+    pub source: ScoreSource,
+    /// Chips added by this step.
+    pub chips_added: i32,
+    /// Factor this step multiplies the running mult by.
+    pub mult_factor: f32,
+    /// Coins paid on top of the chips × mult total.
+    pub coins_added: i32,
+    /// Running totals after this step.
+    pub chips: i32,
+    pub mult: f32,
+}
+
+impl ScoreStep {
+    /// Popup text shown next to the source while the step is revealed.
+    pub fn label(&self) -> String {
+        let mut parts: Vec<String> = vec![];
+
+        if self.chips_added != 0 {
+            parts.push(format!("+{}", self.chips_added));
+        }
+
+        if self.mult_factor != 1.0 {
+            parts.push(format!("x{}", format_mult(self.mult_factor)));
+        }
+
+        if self.coins_added != 0 {
+            parts.push(format!("+${}", self.coins_added));
+        }
+
+        parts.join(" ")
+    }
+}
+
+/// Chips × mult scoring of a played hand, one step per thing that contributed.
+pub struct ScoreBreakdown {
+    pub poker_hand: PokerHand,
+    pub scoring_cards: Vec<Card>,
+    pub steps: Vec<ScoreStep>,
+}
+
+impl ScoreBreakdown {
+    pub fn chips(&self) -> i32 {
+        self.steps.last().map_or(0, |step| step.chips)
+    }
+
+    pub fn mult(&self) -> f32 {
+        self.steps.last().map_or(0.0, |step| step.mult)
+    }
+
+    pub fn total(&self) -> i32 {
+        (self.chips() as f32 * self.mult()).round() as i32
+    }
+
+    /// Coins paid on the side, like for held gold cards, which don't add to the score.
+    pub fn bonus_coins(&self) -> i32 {
+        self.steps.iter().map(|step| step.coins_added).sum()
+    }
+
+    /// Coins the play pays out, the total plus the bonus coins.
+    pub fn payout(&self) -> i32 {
+        self.total() + self.bonus_coins()
+    }
+
+    /// Steps that change nothing are left out, so the animation doesn't stall on them.
+    fn push_step(
+        &mut self,
+        source: ScoreSource,
+        chips_added: i32,
+        mult_factor: f32,
+        coins_added: i32,
+    ) {
+        if chips_added == 0 && mult_factor == 1.0 && coins_added == 0 {
+            return;
+        }
+
+        let chips: i32 = self.chips() + chips_added;
+        let mult: f32 = self.mult() * mult_factor;
+
+        self.steps.push(ScoreStep {
+            source,
+            chips_added,
+            mult_factor,
+            coins_added,
+            chips,
+            mult,
+        });
+    }
+}
+
+/// Reveals a `ScoreBreakdown` step by step, the table stays locked until it's paid out.
+pub struct ScoringAnimation {
+    pub breakdown: ScoreBreakdown,
+    pub elapsed: f32,
+}
+
+impl ScoringAnimation {
+    pub fn revealed_step_count(&self) -> usize {
+        let count: usize = 1 + (self.elapsed / SCORE_STEP_DURATION_SEC) as usize;
+        count.min(self.breakdown.steps.len())
+    }
+
+    pub fn current_step(&self) -> Option<&ScoreStep> {
+        self.breakdown
+            .steps
+            .get(self.revealed_step_count().saturating_sub(1))
+    }
+
+    pub fn all_steps_revealed(&self) -> bool {
+        self.elapsed >= self.breakdown.steps.len() as f32 * SCORE_STEP_DURATION_SEC
+    }
+
+    pub fn is_finished(&self) -> bool {
+        let reveal_duration: f32 = self.breakdown.steps.len() as f32 * SCORE_STEP_DURATION_SEC;
+        self.elapsed >= reveal_duration + SCORE_TOTAL_HOLD_SEC
+    }
+}
+
+/// Mults are shown with at most two decimals and without trailing zeroes.
+pub fn format_mult(mult: f32) -> String {
+    format!("{}", (mult * 100.0).round() / 100.0)
+}
+
+/// Table slot of every scoring card, matched through `Card::resolves_to` since
+/// the evaluator hands back wild cards with their suit and rank filled in.
+fn scoring_card_table_indexes(
+    table_cards: &[Option<Card>],
+    scoring_cards: &[Card],
+) -> Vec<Option<usize>> {
+    let mut used_indexes: Vec<usize> = vec![];
+
+    scoring_cards
+        .iter()
+        .map(|scoring_card| {
+            let maybe_index: Option<usize> = table_cards
+                .iter()
+                .enumerate()
+                .find(|(index, table_card)| {
+                    !used_indexes.contains(index)
+                        && table_card.is_some_and(|card| card.resolves_to(scoring_card))
+                })
+                .map(|(index, _)| index);
+
+            if let Some(index) = maybe_index {
+                used_indexes.push(index);
+            }
+
+            maybe_index
+        })
+        .collect()
+}
+
+pub fn build_score_breakdown(
+    ctx: &Context,
+    poker_hand: PokerHand,
+    scoring_cards: &[Card],
+) -> ScoreBreakdown {
+    let table_cards: Vec<Option<Card>> =
+        ctx.table_card_slots.iter().map(|slot| slot.card).collect();
+    let hand_cards: Vec<Option<Card>> = ctx.hand_card_slots.iter().map(|slot| slot.card).collect();
+
+    build_score_breakdown_for_layout(ctx, poker_hand, scoring_cards, &table_cards, &hand_cards)
+}
+
+/// Same as `build_score_breakdown`, but for cards laid out on the table and in hand
+/// as given instead of where they currently are.
+pub fn build_score_breakdown_for_layout(
+    ctx: &Context,
+    poker_hand: PokerHand,
+    scoring_cards: &[Card],
+    table_cards: &[Option<Card>],
+    hand_cards: &[Option<Card>],
+) -> ScoreBreakdown {
+    let base_chips: i32 = hand_payout(ctx, poker_hand);
+
+    // The hand type sets the base, even when it adds no chips on its own
+    let mut breakdown: ScoreBreakdown = ScoreBreakdown {
+        poker_hand,
+        scoring_cards: scoring_cards.to_vec(),
+        steps: vec![ScoreStep {
+            source: ScoreSource::PokerHand,
+            chips_added: base_chips,
+            mult_factor: 1.0,
+            coins_added: 0,
+            chips: base_chips,
+            mult: poker_hand.base_mult() as f32,
+        }],
+    };
+
+    let table_indexes: Vec<Option<usize>> = scoring_card_table_indexes(table_cards, scoring_cards);
+
+    for (card, table_index) in scoring_cards.iter().zip(table_indexes) {
+        let source: ScoreSource = table_index.map_or(ScoreSource::PokerHand, ScoreSource::Table);
+        let (bonus_chips, mult_factor): (i32, f32) =
+            card.enhancement.map_or((0, 1.0), |enhancement| {
+                (
+                    enhancement.flat_bonus(CardPlacement::Scoring),
+                    enhancement.multiplier(CardPlacement::Scoring),
+                )
+            });
+
+        breakdown.push_step(
+            source,
+            card.rank.coin_value() as i32 + bonus_chips,
+            mult_factor,
+            0,
+        );
+    }
+
+    let played_cards = table_cards
+        .iter()
+        .enumerate()
+        .map(|(index, card)| (ScoreSource::Table(index), CardPlacement::Played, card));
+    let held_cards = hand_cards
+        .iter()
+        .enumerate()
+        .map(|(index, card)| (ScoreSource::Hand(index), CardPlacement::Held, card));
+
+    for (source, placement, card) in played_cards.chain(held_cards) {
+        let Some(enhancement) = card.and_then(|card| card.enhancement) else {
+            continue;
+        };
+
+        breakdown.push_step(
+            source,
+            enhancement.flat_bonus(placement),
+            enhancement.multiplier(placement),
+            enhancement.coin_bonus(placement),
+        );
+    }
+
+    for (index, relic) in ctx.relics.iter().enumerate() {
+        breakdown.push_step(
+            ScoreSource::Relic(index),
+            relic.flat_bonus(poker_hand, scoring_cards),
+            relic.multiplier(poker_hand, scoring_cards),
+            0,
+        );
+    }
+
+    breakdown
+}
+
+pub fn scoring_in_progress(ctx: &Context) -> bool {
+    ctx.scoring.is_some()
+}
+
+/// Evaluates the table and starts revealing its score, `finish_scoring` pays it out.
+pub fn start_scoring(ctx: &mut Context) {
+    let cards: Vec<&Card> = ctx
+        .table_card_slots
+        .iter()
+        .filter_map(|slot| slot.card.as_ref())
+        .collect();
+
+    if cards.is_empty() || scoring_in_progress(ctx) {
+        return;
+    }
+
+    let (poker_hand, scoring_cards): (PokerHand, Vec<Card>) = eval_poker_hand(&cards);
+
+    ctx.scoring = Some(ScoringAnimation {
+        breakdown: build_score_breakdown(ctx, poker_hand, &scoring_cards),
+        elapsed: 0.0,
+    });
+}
+
+pub fn update_scoring(ctx: &mut Context, dt: f32) {
+    let Some(scoring) = ctx.scoring.as_mut() else {
+        return;
+    };
+
+    scoring.elapsed += dt;
+
+    if scoring.is_finished() {
+        finish_scoring(ctx);
+    }
+}
+
+/// Pays out the scoring in progress and clears the table.
+pub fn finish_scoring(ctx: &mut Context) {
+    let Some(scoring) = ctx.scoring.take() else {
+        return;
+    };
+
+    let breakdown: ScoreBreakdown = scoring.breakdown;
+
+    ctx.coins += breakdown.payout();
+    ctx.score += breakdown.total();
+    break_glass_cards(ctx, &breakdown.scoring_cards);
+    record_played_hand(ctx, breakdown.poker_hand);
+
+    // Weak hands are compensated with luck
+    if breakdown.poker_hand <= PokerHand::Pair {
+        ctx.luck += 1;
+    }
+
+    // Clear hand
+    ctx.table_card_slots.iter_mut().for_each(|slot| {
+        slot.card = None;
+    });
+    update_current_poker_hand(ctx);
+}
+
+/// Whether the step currently being revealed comes from `source`.
+pub fn is_scoring_source_active(ctx: &Context, source: ScoreSource) -> bool {
+    ctx.scoring
+        .as_ref()
+        .and_then(|scoring| scoring.current_step())
+        .is_some_and(|step| step.source == source)
+}
+
+/// Running chips × mult in place of the poker hand preview, plus a popup at the current source.
+pub fn draw_score_breakdown(draw_queue: &mut Vec<DrawCall>, ctx: &Context) {
+    let Some(scoring) = ctx.scoring.as_ref() else {
+        return;
+    };
+    let Some(step) = scoring.current_step() else {
+        return;
+    };
+
+    let breakdown: &ScoreBreakdown = &scoring.breakdown;
+    let total_text: String = if scoring.all_steps_revealed() && breakdown.bonus_coins() != 0 {
+        format!(" = {} +${}", breakdown.total(), breakdown.bonus_coins())
+    } else if scoring.all_steps_revealed() {
+        format!(" = {}", breakdown.total())
+    } else {
+        String::new()
+    };
+
+    let segments: [(String, Rgba); 5] = [
+        (
+            format!(
+                "{} Lv{}  ",
+                breakdown.poker_hand.repr(),
+                hand_level(ctx, breakdown.poker_hand)
+            ),
+            Rgba::from_u8(255, 255, 255, 1.0),
+        ),
+        (format!("{}", step.chips), CHIPS_COLOR),
+        (" x ".to_string(), Rgba::from_u8(200, 200, 200, 1.0)),
+        (format_mult(step.mult), MULT_COLOR),
+        (total_text, COINS_COLOR),
+    ];
+
+    // Centered in the play area like the poker hand preview it replaces
+    let line_width: u16 = segments
+        .iter()
+        .map(|(text, _)| text.chars().count() as u16)
+        .sum();
+    let mut segment_x: u16 = SIDEBAR_BORDER_X.saturating_sub(line_width) / 2;
+
+    for (text, color) in segments {
+        let text_width: u16 = text.chars().count() as u16;
+        draw_text(
+            draw_queue,
+            segment_x,
+            18,
+            RichText::new(text).with_fg(color).with_bold(true),
+        );
+        segment_x += text_width;
+    }
+
+    let (source_x, source_width, popup_y): (u16, u16, u16) = match step.source {
+        ScoreSource::PokerHand => return,
+        ScoreSource::Table(index) => (
+            TABLE_ORIGIN_X + index as u16 * TABLE_CARD_X_SPACING,
+            TABLE_CARD_X_SPACING,
+            TABLE_ORIGIN_Y - 1,
+        ),
+        ScoreSource::Hand(index) => (
+            HAND_ORIGIN_X + index as u16 * HAND_CARD_X_SPACING,
+            HAND_CARD_X_SPACING,
+            HAND_ORIGIN_Y + 3,
+        ),
+        ScoreSource::Relic(index) => (
+            RELICS_ORIGIN_X + index as u16 * RELIC_TILE_WIDTH,
+            RELIC_TILE_WIDTH,
+            RELICS_ORIGIN_Y + 1,
+        ),
+    };
+
+    // Popup centered under or over the source, kept on screen
+    let label: String = step.label();
+    let label_width: u16 = label.chars().count() as u16;
+    let popup_x: u16 = (source_x + source_width / 2)
+        .saturating_sub(label_width / 2)
+        .min(TERM_SCREEN_WIDTH.saturating_sub(label_width));
+    let popup_color: Rgba = if step.chips_added != 0 {
+        CHIPS_COLOR
+    } else if step.coins_added != 0 {
+        COINS_COLOR
+    } else {
+        MULT_COLOR
+    };
+
+    draw_text(
+        draw_queue,
+        popup_x,
+        popup_y,
+        RichText::new(label)
+            .with_fg(Rgba::from_u8(0, 0, 0, 1.0))
+            .with_bg(popup_color)
+            .with_bold(true),
+    );
+}
+
+/// Rolls every scoring glass card for breaking. A broken card loses one copy from the reels,
+/// unless every reel holding it is already at `MIN_REEL_SIZE`.
+pub fn break_glass_cards(ctx: &mut Context, scoring_cards: &[Card]) {
+    for card in scoring_cards {
+        if card.enhancement != Some(Enhancement::Glass) || !ctx.rng.random_bool(GLASS_BREAK_CHANCE)
+        {
+            continue;
+        }
+
+        for column in ctx.slot_machine.columns.iter_mut() {
+            if column.cards.len() <= MIN_REEL_SIZE {
+                continue;
+            }
+
+            if let Some(index) = column
+                .cards
+                .iter()
+                .position(|reel_card| reel_card.card.resolves_to(card))
+            {
+                column.cards.remove(index);
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use std::{
+    fs, io,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    context::{Context, ImpulseId},
+    renderer::{Cell, ScreenBuffer, TextAttributes},
+};
+
+/// Opacity of dim text over its background, halfway between the two colors.
+const DIM_OPACITY: f32 = 0.5;
+/// SVG cell size in pixels, the font size is picked to fill it like a terminal would.
+const SVG_CELL_WIDTH: u32 = 9;
+const SVG_CELL_HEIGHT: u32 = 18;
+const SVG_FONT_SIZE: u32 = 15;
+
+#[derive(Clone, Copy)]
+pub enum ScreenshotFormat {
+    Html,
+    Svg,
+}
+
+impl ScreenshotFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ScreenshotFormat::Html => "html",
+            ScreenshotFormat::Svg => "svg",
+        }
+    }
+}
+
+/// Writes the frame currently on screen to `screenshot-<unix millis>.<ext>` in the working
+/// directory and flashes the outcome in the sidebar.
+pub fn save_screenshot(ctx: &mut Context, format: ScreenshotFormat) {
+    // `old_buffer` holds the last presented frame until the next one is drawn
+    let buffer: &ScreenBuffer = &ctx.screen.old_buffer;
+    let contents: String = match format {
+        ScreenshotFormat::Html => screen_buffer_to_html(buffer),
+        ScreenshotFormat::Svg => screen_buffer_to_svg(buffer),
+    };
+
+    let impulse_id: ImpulseId = match write_screenshot(&contents, format) {
+        Ok(()) => ImpulseId::ScreenshotSaved,
+        Err(_) => ImpulseId::ScreenshotFailed,
+    };
+    ctx.impulse_timestamps.insert(impulse_id, ctx.game_time);
+}
+
+fn write_screenshot(contents: &str, format: ScreenshotFormat) -> io::Result<()> {
+    let unix_millis: u128 = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_millis());
+    let path: PathBuf = PathBuf::from(format!("screenshot-{unix_millis}.{}", format.extension()));
+
+    fs::write(path, contents)
+}
+
+fn escape_markup(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// A `<pre>` block with one inline styled `<span>` per run of equally styled cells. Spans carry
+/// the cell colors as drawn, dim, reverse and blink are classes the page styles.
+pub fn screen_buffer_to_html(buf: &ScreenBuffer) -> String {
+    let mut body: String = String::new();
+
+    for row in buf.cells.chunks(buf.width as usize) {
+        let mut run_start: usize = 0;
+
+        for x in 1..=row.len() {
+            if x < row.len() && row[x].same_style(&row[run_start]) {
+                continue;
+            }
+
+            let cell: &Cell = &row[run_start];
+            let text: String = row[run_start..x]
+                .iter()
+                .map(|cell| escape_markup(&cell.printed_text()))
+                .collect();
+            let mut style: String = format!("--fg:#{:06x};--bg:#{:06x}", cell.fg, cell.bg);
+            if cell.bold {
+                style.push_str(";font-weight:bold");
+            }
+            if cell.attributes.contains(TextAttributes::ITALIC) {
+                style.push_str(";font-style:italic");
+            }
+            if let Some(decoration) = text_decoration(cell) {
+                style.push_str(&format!(";text-decoration:{decoration}"));
+            }
+            if let Some(underline_color) = cell.underline_color {
+                style.push_str(&format!(";text-decoration-color:#{underline_color:06x}"));
+            }
+            let classes: Vec<&str> = [
+                (TextAttributes::DIM, "dim"),
+                (TextAttributes::REVERSE, "reverse"),
+                (TextAttributes::BLINK, "blink"),
+            ]
+            .into_iter()
+            .filter(|(flag, _)| cell.attributes.contains(*flag))
+            .map(|(_, class)| class)
+            .collect();
+
+            if classes.is_empty() {
+                body.push_str(&format!("<span style=\"{style}\">{text}</span>"));
+            } else {
+                body.push_str(&format!(
+                    "<span class=\"{}\" style=\"{style}\">{text}</span>",
+                    classes.join(" ")
+                ));
+            }
+            run_start = x;
+        }
+
+        body.push('\n');
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>term-slots</title>\n\
+         <style>body{{margin:0;background:#000}}pre{{margin:0;font-family:monospace;\
+         line-height:1.2;font-size:15px}}span{{color:var(--fg);background:var(--bg)}}\
+         .reverse{{color:var(--bg);background:var(--fg)}}\
+         .dim{{color:color-mix(in srgb,var(--fg) {dim_percent}%,var(--bg))}}\
+         .dim.reverse{{color:color-mix(in srgb,var(--bg) {dim_percent}%,var(--fg))}}\
+         .blink{{animation:blink 1s steps(1) infinite}}@keyframes blink{{50%{{color:transparent}}}}\
+         </style>\n</head>\n<body>\n<pre>{body}</pre>\n</body>\n</html>\n",
+        dim_percent = DIM_OPACITY * 100.0
+    )
+}
+
+/// Text and background fills of a cell, trading places for reverse. Dim is left to
+/// `fill-opacity` and blink is left out, a still image can't show it.
+fn svg_fills(cell: &Cell) -> (u32, u32) {
+    if cell.attributes.contains(TextAttributes::REVERSE) {
+        (cell.bg, cell.fg)
+    } else {
+        (cell.fg, cell.bg)
+    }
+}
+
+/// Underline and strikethrough as a `text-decoration` value, `None` without either.
+fn text_decoration(cell: &Cell) -> Option<String> {
+    let lines: Vec<&str> = [
+        (TextAttributes::UNDERLINE, "underline"),
+        (TextAttributes::STRIKETHROUGH, "line-through"),
+    ]
+    .into_iter()
+    .filter(|(flag, _)| cell.attributes.contains(*flag))
+    .map(|(_, line)| line)
+    .collect();
+
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.join(" "))
+    }
+}
+
+/// Background runs as rects, then every visible character on its own grid position,
+/// so glyph widths can't shift the layout.
+pub fn screen_buffer_to_svg(buf: &ScreenBuffer) -> String {
+    let width: u32 = buf.width as u32 * SVG_CELL_WIDTH;
+    let height: u32 = buf.height as u32 * SVG_CELL_HEIGHT;
+    let mut elements: Vec<String> = vec![];
+
+    for (y, row) in buf.cells.chunks(buf.width as usize).enumerate() {
+        let mut run_start: usize = 0;
+
+        for x in 1..=row.len() {
+            if x < row.len() && svg_fills(&row[x]).1 == svg_fills(&row[run_start]).1 {
+                continue;
+            }
+
+            elements.push(format!(
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{SVG_CELL_HEIGHT}\" fill=\"#{:06x}\"/>",
+                run_start as u32 * SVG_CELL_WIDTH,
+                y as u32 * SVG_CELL_HEIGHT,
+                (x - run_start) as u32 * SVG_CELL_WIDTH,
+                svg_fills(&row[run_start]).1
+            ));
+            run_start = x;
+        }
+    }
+
+    for (index, cell) in buf.cells.iter().enumerate() {
+        // Spaces only show up when a line runs through them
+        let decoration: Option<String> = text_decoration(cell);
+        if cell.ch == ' ' && decoration.is_none() {
+            continue;
+        }
+
+        let x: u32 = (index % buf.width as usize) as u32;
+        let y: u32 = (index / buf.width as usize) as u32;
+        let mut attributes: String = String::new();
+        if cell.bold {
+            attributes.push_str(" font-weight=\"bold\"");
+        }
+        if cell.attributes.contains(TextAttributes::ITALIC) {
+            attributes.push_str(" font-style=\"italic\"");
+        }
+        if cell.attributes.contains(TextAttributes::DIM) {
+            attributes.push_str(&format!(" fill-opacity=\"{DIM_OPACITY}\""));
+        }
+        if let Some(decoration) = decoration {
+            attributes.push_str(&format!(" text-decoration=\"{decoration}\""));
+        }
+
+        elements.push(format!(
+            "<text x=\"{}\" y=\"{}\" fill=\"#{:06x}\"{attributes}>{}</text>",
+            x * SVG_CELL_WIDTH,
+            y * SVG_CELL_HEIGHT + SVG_CELL_HEIGHT * 3 / 4,
+            svg_fills(cell).0,
+            escape_markup(&cell.printed_text())
+        ));
+    }
+
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+         viewBox=\"0 0 {width} {height}\" font-family=\"monospace\" font-size=\"{SVG_FONT_SIZE}\" \
+         xml:space=\"preserve\">\n{}\n</svg>\n",
+        elements.join("\n")
+    )
+}
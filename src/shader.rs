@@ -1,5 +1,5 @@
 use crate::renderer::{
-    DrawCall, Hsl, Rgba, RichText, ScreenBuffer, blend_source_over, packed_rgb_to_rgba,
+    DrawCall, Hsl, Layer, Rgba, RichText, ScreenBuffer, blend_source_over, packed_rgb_to_rgba,
     rgba_to_packed_rgb,
 };
 
@@ -87,7 +87,7 @@ pub fn draw_bg_shader(
 
             let cell_x: u16 = x / 2;
             let cell_y: u16 = y;
-            let is_checker: bool = (cell_x + cell_y) % 2 == 0;
+            let is_checker: bool = (cell_x + cell_y).is_multiple_of(2);
 
             let offset: f32 = (cell_x as f32 / 8.0) + (cell_y as f32 / 4.0);
             let phase: f32 =
@@ -122,6 +122,7 @@ pub fn draw_bg_shader(
                 rich_text: RichText::new(" ")
                     .with_fg(Rgba::from_u8(0, 0, 0, 1.0))
                     .with_bg(color),
+                layer: Layer::default(),
             });
         }
     }
@@ -0,0 +1,437 @@
+use rand::{Rng, seq::IndexedRandom};
+
+use crate::{
+    button::{Button, ButtonId},
+    card::{Card, Enhancement, Rank, Suit, WildKind, standard_52_deck},
+    context::Context,
+    hand::{MAX_HAND_SLOT_COUNT, add_hand_slot},
+    poker_hand::{PokerHand, level_up_hand},
+    reel_editor::open_reel_editor,
+    relic::{MAX_RELIC_COUNT, Relic, unowned_relics},
+    renderer::{DrawCall, Rgba, RichText, draw_rect, draw_text},
+    rng::GameRng,
+    run::{RunPhase, leave_shop},
+    slot_machine::{MAX_REEL_SIZE, MIN_REEL_SIZE, ReelCard},
+    utils::center_text_unicode,
+};
+
+pub const SHOP_PANEL_X: u16 = 1;
+pub const SHOP_PANEL_Y: u16 = 11;
+pub const SHOP_PANEL_WIDTH: u16 = 35;
+pub const SHOP_PANEL_HEIGHT: u16 = 18;
+pub const MAX_SHOP_STOCK: usize = 4;
+
+const SHOP_ITEMS_ORIGIN_Y: u16 = SHOP_PANEL_Y + 3;
+const SHOP_ITEM_Y_SPACING: u16 = 3;
+const BUY_BUTTON_WIDTH: u16 = 7;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ShopItem {
+    Relic(Relic),
+    HandSlot,
+    ReelEdit(ReelEdit),
+    /// Enhances a few random unenhanced reel cards.
+    Enhance(Enhancement),
+    /// Levels up a poker hand for the rest of the run.
+    HandUpgrade(PokerHand),
+    Consumable(Consumable),
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ReelEdit {
+    /// Removes a few low ranked cards from every reel.
+    Thin,
+    /// Adds an Ace of a random suit to every reel.
+    AceUp,
+    /// Adds a wild card of a random kind to every reel.
+    Wild,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Consumable {
+    LuckCharm,
+    ExtraSpins,
+}
+
+#[derive(Default)]
+pub struct Shop {
+    /// Sold items leave an empty spot behind until the next restock.
+    pub stock: Vec<Option<ShopItem>>,
+    pub reroll_count: i32,
+}
+
+impl ShopItem {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ShopItem::Relic(relic) => relic.name(),
+            ShopItem::HandSlot => "Deep Pockets",
+            ShopItem::ReelEdit(ReelEdit::Thin) => "Reel Thinner",
+            ShopItem::ReelEdit(ReelEdit::AceUp) => "Ace Up",
+            ShopItem::ReelEdit(ReelEdit::Wild) => "Wild Cards",
+            ShopItem::Enhance(Enhancement::Foil) => "Foil Wrap",
+            ShopItem::Enhance(Enhancement::Gold) => "Gold Leaf",
+            ShopItem::Enhance(Enhancement::Glass) => "Glassblower",
+            ShopItem::Enhance(Enhancement::Stone) => "Petrify",
+            ShopItem::Enhance(Enhancement::Steel) => "Steel Plating",
+            ShopItem::HandUpgrade(poker_hand) => poker_hand.repr(),
+            ShopItem::Consumable(Consumable::LuckCharm) => "Luck Charm",
+            ShopItem::Consumable(Consumable::ExtraSpins) => "Extra Spins",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            ShopItem::Relic(relic) => relic.description(),
+            ShopItem::HandSlot => "+1 hand slot",
+            ShopItem::ReelEdit(ReelEdit::Thin) => "Remove 4 cards 2-5 from each reel",
+            ShopItem::ReelEdit(ReelEdit::AceUp) => "Add an Ace to every reel",
+            ShopItem::ReelEdit(ReelEdit::Wild) => "Add a wild card to every reel",
+            ShopItem::Enhance(enhancement) => enhancement.description(),
+            ShopItem::HandUpgrade(_) => "Level up this poker hand",
+            ShopItem::Consumable(Consumable::LuckCharm) => "+5 luck",
+            ShopItem::Consumable(Consumable::ExtraSpins) => "+2 spins next round",
+        }
+    }
+
+    pub fn color(&self) -> Rgba {
+        match self {
+            ShopItem::Relic(relic) => relic.color(),
+            ShopItem::HandSlot => Rgba::from_u8(255, 200, 160, 1.0),
+            ShopItem::ReelEdit(_) => Rgba::from_u8(255, 150, 90, 1.0),
+            ShopItem::Enhance(enhancement) => enhancement.bg_color(),
+            ShopItem::HandUpgrade(_) => Rgba::from_u8(190, 230, 255, 1.0),
+            ShopItem::Consumable(_) => Rgba::from_u8(150, 220, 255, 1.0),
+        }
+    }
+
+    fn base_price(&self) -> i32 {
+        match self {
+            ShopItem::Relic(_) => 50,
+            ShopItem::HandSlot => 80,
+            ShopItem::ReelEdit(_) => 35,
+            ShopItem::Enhance(_) => 30,
+            ShopItem::HandUpgrade(_) => 25,
+            ShopItem::Consumable(_) => 20,
+        }
+    }
+
+    /// Prices scale with the round the shop is opened before.
+    pub fn price(&self, round: i32) -> i32 {
+        let base_price: i32 = self.base_price();
+        base_price + base_price * (round - 1) / 4
+    }
+}
+
+pub fn shop_stock_size(round: i32) -> usize {
+    (2 + round as usize / 2).min(MAX_SHOP_STOCK)
+}
+
+pub fn reroll_cost(ctx: &Context) -> i32 {
+    5 + ctx.run.round + ctx.shop.reroll_count * 3
+}
+
+/// Fills the shop with fresh stock, relics never appear twice in the same stock.
+pub fn restock_shop(ctx: &mut Context) {
+    let mut stock: Vec<Option<ShopItem>> = vec![];
+    let mut relic_pool: Vec<Relic> = unowned_relics(ctx);
+    let hand_slot_available: bool = ctx.hand_card_slots.len() < MAX_HAND_SLOT_COUNT;
+    let rng: &mut GameRng = &mut ctx.rng;
+
+    for _ in 0..shop_stock_size(ctx.run.round) {
+        let roll: f32 = rng.random();
+
+        let item: ShopItem = if roll < 0.4 && !relic_pool.is_empty() {
+            let relic_index: usize = rng.random_range(0..relic_pool.len());
+            ShopItem::Relic(relic_pool.swap_remove(relic_index))
+        } else if roll < 0.5 && hand_slot_available {
+            ShopItem::HandSlot
+        } else if roll < 0.65 {
+            *[
+                ShopItem::ReelEdit(ReelEdit::Thin),
+                ShopItem::ReelEdit(ReelEdit::AceUp),
+                ShopItem::ReelEdit(ReelEdit::Wild),
+            ]
+            .choose(rng)
+            .expect("Not empty")
+        } else if roll < 0.75 {
+            let enhancements: Vec<Enhancement> = Enhancement::iter().collect();
+            ShopItem::Enhance(*enhancements.choose(rng).expect("Not empty"))
+        } else if roll < 0.88 {
+            let poker_hands: Vec<PokerHand> = PokerHand::iter().collect();
+            ShopItem::HandUpgrade(*poker_hands.choose(rng).expect("Not empty"))
+        } else {
+            *[
+                ShopItem::Consumable(Consumable::LuckCharm),
+                ShopItem::Consumable(Consumable::ExtraSpins),
+            ]
+            .choose(rng)
+            .expect("Not empty")
+        };
+
+        stock.push(Some(item));
+    }
+
+    ctx.shop.stock = stock;
+}
+
+pub fn can_buy_shop_item(ctx: &Context, index: usize) -> bool {
+    let Some(Some(item)) = ctx.shop.stock.get(index) else {
+        return false;
+    };
+
+    let has_room: bool = match item {
+        ShopItem::Relic(_) => ctx.relics.len() < MAX_RELIC_COUNT,
+        ShopItem::HandSlot => ctx.hand_card_slots.len() < MAX_HAND_SLOT_COUNT,
+        _ => true,
+    };
+
+    has_room && ctx.coins >= item.price(ctx.run.round)
+}
+
+pub fn buy_shop_item(ctx: &mut Context, index: usize) {
+    if !can_buy_shop_item(ctx, index) {
+        return;
+    }
+
+    let item: ShopItem = ctx.shop.stock[index]
+        .take()
+        .expect("We already checked this exists");
+    ctx.coins -= item.price(ctx.run.round);
+
+    match item {
+        ShopItem::Relic(relic) => ctx.relics.push(relic),
+        ShopItem::HandSlot => add_hand_slot(ctx),
+        ShopItem::ReelEdit(reel_edit) => apply_reel_edit(ctx, reel_edit),
+        ShopItem::Enhance(enhancement) => enhance_reel_cards(ctx, enhancement),
+        ShopItem::HandUpgrade(poker_hand) => level_up_hand(ctx, poker_hand),
+        ShopItem::Consumable(Consumable::LuckCharm) => ctx.luck += 5,
+        ShopItem::Consumable(Consumable::ExtraSpins) => ctx.run.bonus_spins += 2,
+    }
+}
+
+fn apply_reel_edit(ctx: &mut Context, reel_edit: ReelEdit) {
+    let rng: &mut GameRng = &mut ctx.rng;
+
+    for column in ctx.slot_machine.columns.iter_mut() {
+        match reel_edit {
+            ReelEdit::Thin => {
+                for _ in 0..4 {
+                    let low_card_indexes: Vec<usize> = column
+                        .cards
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, reel_card)| {
+                            matches!(
+                                reel_card.card.rank,
+                                Rank::Num2 | Rank::Num3 | Rank::Num4 | Rank::Num5
+                            )
+                        })
+                        .map(|(index, _)| index)
+                        .collect();
+
+                    if column.cards.len() <= MIN_REEL_SIZE {
+                        break;
+                    }
+
+                    if let Some(&index) = low_card_indexes.choose(rng) {
+                        column.cards.remove(index);
+                    }
+                }
+            }
+            ReelEdit::AceUp => {
+                let suit: Suit = *[Suit::Spade, Suit::Heart, Suit::Club, Suit::Diamond]
+                    .choose(rng)
+                    .expect("Not empty");
+                if column.cards.len() >= MAX_REEL_SIZE {
+                    continue;
+                }
+
+                let index: usize = rng.random_range(0..=column.cards.len());
+                column.cards.insert(
+                    index,
+                    ReelCard {
+                        card: Card::new(suit, Rank::Ace),
+                        weight: 1,
+                    },
+                );
+            }
+            ReelEdit::Wild => {
+                if column.cards.len() >= MAX_REEL_SIZE {
+                    continue;
+                }
+
+                let mut card: Card = *standard_52_deck().choose(rng).expect("Not empty");
+                card.wild = WildKind::iter().collect::<Vec<_>>().choose(rng).copied();
+
+                let index: usize = rng.random_range(0..=column.cards.len());
+                column.cards.insert(index, ReelCard { card, weight: 1 });
+            }
+        }
+    }
+}
+
+/// Enhances random reel cards across all columns, already enhanced cards are left alone.
+fn enhance_reel_cards(ctx: &mut Context, enhancement: Enhancement) {
+    const ENHANCED_CARD_COUNT: usize = 3;
+
+    let candidates: Vec<(usize, usize)> = ctx
+        .slot_machine
+        .columns
+        .iter()
+        .enumerate()
+        .flat_map(|(column_index, column)| {
+            column
+                .cards
+                .iter()
+                .enumerate()
+                .filter(|(_, reel_card)| reel_card.card.enhancement.is_none())
+                .map(move |(card_index, _)| (column_index, card_index))
+        })
+        .collect();
+
+    for &(column_index, card_index) in candidates.choose_multiple(&mut ctx.rng, ENHANCED_CARD_COUNT)
+    {
+        ctx.slot_machine.columns[column_index].cards[card_index]
+            .card
+            .enhancement = Some(enhancement);
+    }
+}
+
+pub fn reroll_shop(ctx: &mut Context) {
+    let cost: i32 = reroll_cost(ctx);
+    if ctx.coins < cost {
+        return;
+    }
+
+    ctx.coins -= cost;
+    ctx.shop.reroll_count += 1;
+    restock_shop(ctx);
+}
+
+/// The reel editor takes over the shop panel while it's open.
+fn shop_is_open(ctx: &Context) -> bool {
+    matches!(ctx.run.phase, RunPhase::Shop) && ctx.reel_editor.is_none()
+}
+
+pub fn push_shop_buttons(buttons: &mut Vec<Button>, ctx: &Context) {
+    if !shop_is_open(ctx) {
+        return;
+    }
+
+    for (index, item) in ctx.shop.stock.iter().enumerate() {
+        let Some(item) = item else {
+            continue;
+        };
+
+        buttons.push(Button {
+            id: ButtonId::BuyShopItem { index },
+            x: SHOP_PANEL_X + SHOP_PANEL_WIDTH - BUY_BUTTON_WIDTH - 2,
+            y: SHOP_ITEMS_ORIGIN_Y + index as u16 * SHOP_ITEM_Y_SPACING,
+            w: BUY_BUTTON_WIDTH,
+            h: 1,
+            text: format!("${}", item.price(ctx.run.round)),
+            color: Rgba::from_u8(255, 210, 140, 1.0),
+            on_click: Box::new(move |ctx: &mut Context| buy_shop_item(ctx, index)),
+            enabled_when: Box::new(move |ctx: &Context| can_buy_shop_item(ctx, index)),
+            allow_rmb: false,
+        });
+    }
+
+    let bottom_y: u16 = SHOP_PANEL_Y + SHOP_PANEL_HEIGHT - 2;
+
+    buttons.push(Button {
+        id: ButtonId::Reroll,
+        x: SHOP_PANEL_X,
+        y: bottom_y,
+        w: 11,
+        h: 1,
+        text: format!("${} REROLL", reroll_cost(ctx)),
+        color: Rgba::from_u8(150, 220, 255, 1.0),
+        on_click: Box::new(reroll_shop),
+        enabled_when: Box::new(|ctx: &Context| ctx.coins >= reroll_cost(ctx)),
+        allow_rmb: false,
+    });
+
+    buttons.push(Button {
+        id: ButtonId::OpenReelEditor,
+        x: SHOP_PANEL_X + 12,
+        y: bottom_y,
+        w: 11,
+        h: 1,
+        text: "REELS".to_string(),
+        color: Rgba::from_u8(255, 150, 90, 1.0),
+        on_click: Box::new(open_reel_editor),
+        enabled_when: Box::new(|_| true),
+        allow_rmb: false,
+    });
+
+    buttons.push(Button {
+        id: ButtonId::LeaveShop,
+        x: SHOP_PANEL_X + 24,
+        y: bottom_y,
+        w: 11,
+        h: 1,
+        text: "CONTINUE".to_string(),
+        color: Rgba::from_u8(160, 210, 140, 1.0),
+        on_click: Box::new(leave_shop),
+        enabled_when: Box::new(|_| true),
+        allow_rmb: false,
+    });
+}
+
+pub fn draw_shop(draw_queue: &mut Vec<DrawCall>, ctx: &Context) {
+    if !shop_is_open(ctx) {
+        return;
+    }
+
+    draw_rect(
+        draw_queue,
+        SHOP_PANEL_X as i16,
+        SHOP_PANEL_Y as i16,
+        SHOP_PANEL_WIDTH,
+        SHOP_PANEL_HEIGHT,
+        Rgba::from_u8(20, 10, 10, 0.95),
+    );
+
+    draw_text(
+        draw_queue,
+        SHOP_PANEL_X,
+        SHOP_PANEL_Y + 1,
+        RichText::new(center_text_unicode(
+            format!("SHOP - next up: round {}", ctx.run.round),
+            SHOP_PANEL_WIDTH as usize,
+        ))
+        .with_fg(Rgba::from_u8(255, 210, 140, 1.0))
+        .with_bold(true),
+    );
+
+    for (index, item) in ctx.shop.stock.iter().enumerate() {
+        let x: u16 = SHOP_PANEL_X + 2;
+        let y: u16 = SHOP_ITEMS_ORIGIN_Y + index as u16 * SHOP_ITEM_Y_SPACING;
+
+        let Some(item) = item else {
+            draw_text(
+                draw_queue,
+                x,
+                y,
+                RichText::new("SOLD").with_fg(Rgba::from_u8(110, 90, 90, 1.0)),
+            );
+            continue;
+        };
+
+        draw_text(
+            draw_queue,
+            x,
+            y,
+            RichText::new(item.name())
+                .with_fg(item.color())
+                .with_bold(true),
+        );
+        draw_text(
+            draw_queue,
+            x,
+            y + 1,
+            RichText::new(item.description()).with_fg(Rgba::from_u8(200, 200, 200, 1.0)),
+        );
+    }
+}
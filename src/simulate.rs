@@ -0,0 +1,177 @@
+use std::{
+    collections::HashMap,
+    io::{self, ErrorKind},
+};
+
+use crate::{
+    button::Button,
+    context::Context,
+    game::{build_buttons, update_game},
+    input::resolve_input,
+    poker_hand::PokerHand,
+    rng::reseed,
+    run::{FINAL_ROUND, RunPhase},
+    slot_machine::shuffle_slot_machine,
+    strategy::{STRATEGY_NAMES, Strategy, build_strategy},
+};
+
+/// Fixed timestep of simulated ticks, only animations depend on it.
+const SIM_DT: f32 = 1.0 / 30.0;
+/// Games still going after this many ticks are counted as stuck.
+const MAX_SIM_TICKS: usize = 200_000;
+
+pub struct GameResult {
+    pub phase: RunPhase,
+    pub score: i32,
+    pub round: i32,
+    pub spin_count: i32,
+    pub hand_play_counts: HashMap<PokerHand, i32>,
+    /// Coins when every round was reached, before shopping for it, round 1 first.
+    pub round_start_coins: Vec<i32>,
+}
+
+/// Plays one full run with no terminal, feeding the strategy's input through the same
+/// buttons and input handling a real session uses.
+pub fn simulate_game(strategy: &mut dyn Strategy, seed: u64) -> GameResult {
+    let mut ctx: Context = Context::default();
+    reseed(&mut ctx, seed);
+    shuffle_slot_machine(&mut ctx.slot_machine, &mut ctx.rng);
+
+    let mut round_start_coins: Vec<i32> = vec![ctx.coins];
+
+    for _ in 0..MAX_SIM_TICKS {
+        if matches!(ctx.run.phase, RunPhase::GameOver | RunPhase::Victory) {
+            break;
+        }
+
+        let buttons: Vec<Button> = build_buttons(&ctx);
+        for event in strategy.act(&ctx, &buttons) {
+            resolve_input(&mut ctx, event, &buttons);
+        }
+
+        update_game(&mut ctx, SIM_DT);
+        ctx.game_time += SIM_DT;
+
+        // Recorded as soon as a round is reached, a run can end before the shop is left
+        if ctx.run.round as usize > round_start_coins.len() {
+            round_start_coins.push(ctx.coins);
+        }
+    }
+
+    GameResult {
+        phase: ctx.run.phase,
+        score: ctx.score,
+        round: ctx.run.round,
+        spin_count: ctx.slot_machine.spin_count,
+        hand_play_counts: ctx.hand_play_counts,
+        round_start_coins,
+    }
+}
+
+/// Runs `game_count` games with seeds counting up from `seed` and prints aggregate stats.
+pub fn run_simulation(game_count: usize, strategy_name: &str, seed: u64) -> io::Result<()> {
+    let mut results: Vec<GameResult> = vec![];
+
+    for game_index in 0..game_count {
+        let game_seed: u64 = seed.wrapping_add(game_index as u64);
+        let mut strategy: Box<dyn Strategy> =
+            build_strategy(strategy_name, game_seed).ok_or_else(|| {
+                io::Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "Unknown strategy `{strategy_name}`, expected one of: {}",
+                        STRATEGY_NAMES.join(", ")
+                    ),
+                )
+            })?;
+
+        results.push(simulate_game(strategy.as_mut(), game_seed));
+    }
+
+    print!(
+        "{}",
+        format_simulation_report(&results, strategy_name, seed)
+    );
+    Ok(())
+}
+
+fn format_simulation_report(results: &[GameResult], strategy_name: &str, seed: u64) -> String {
+    let game_count: usize = results.len().max(1);
+    let average = |value: &dyn Fn(&GameResult) -> i32| -> f32 {
+        results
+            .iter()
+            .map(|result| value(result) as f32)
+            .sum::<f32>()
+            / game_count as f32
+    };
+    let phase_count = |phase: RunPhase| -> usize {
+        results
+            .iter()
+            .filter(|result| result.phase == phase)
+            .count()
+    };
+
+    let mut lines: Vec<String> = vec![
+        format!(
+            "{} games, strategy {strategy_name}, seeds {seed}..",
+            results.len()
+        ),
+        format!(
+            "victories {}, game overs {}, stuck {}",
+            phase_count(RunPhase::Victory),
+            phase_count(RunPhase::GameOver),
+            results.len() - phase_count(RunPhase::Victory) - phase_count(RunPhase::GameOver),
+        ),
+        format!(
+            "average score {:.1}, best {}",
+            average(&|result| result.score),
+            results.iter().map(|result| result.score).max().unwrap_or(0),
+        ),
+        format!("average round {:.2}", average(&|result| result.round)),
+        format!("average spins {:.1}", average(&|result| result.spin_count)),
+        String::new(),
+        "hands played".to_string(),
+    ];
+
+    let mut hand_counts: HashMap<PokerHand, i32> = HashMap::new();
+    for result in results {
+        for (poker_hand, count) in &result.hand_play_counts {
+            *hand_counts.entry(*poker_hand).or_insert(0) += count;
+        }
+    }
+    let total_hands: i32 = hand_counts.values().sum::<i32>().max(1);
+
+    for poker_hand in PokerHand::iter().rev() {
+        let count: i32 = hand_counts.get(&poker_hand).copied().unwrap_or(0);
+        if count == 0 {
+            continue;
+        }
+
+        lines.push(format!(
+            "  {:<16}{count:>8} {:>6.2}%",
+            poker_hand.repr(),
+            count as f32 / total_hands as f32 * 100.0,
+        ));
+    }
+
+    lines.push(String::new());
+    lines.push("coins on reaching each round".to_string());
+
+    for round in 1..=FINAL_ROUND {
+        let coins: Vec<i32> = results
+            .iter()
+            .filter_map(|result| result.round_start_coins.get(round as usize - 1).copied())
+            .collect();
+        if coins.is_empty() {
+            break;
+        }
+
+        lines.push(format!(
+            "  round {round}: {:>8.1} avg over {} games",
+            coins.iter().sum::<i32>() as f32 / coins.len() as f32,
+            coins.len(),
+        ));
+    }
+
+    lines.join("\n") + "\n"
+}
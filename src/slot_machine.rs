@@ -1,8 +1,12 @@
+use rand::seq::SliceRandom;
+
 use crate::{
-    card::{Card, draw_calls_playing_card_small},
+    card::{Card, Rank, draw_calls_playing_card_small},
     card_ops::CardDragState,
     context::Context,
-    renderer::{DrawCall, Hsl, Rgba, RichText, draw_rect, point_in_rect},
+    luck::{LUCK_COLOR, nudge_column_towards_lucky_ranks},
+    renderer::{DrawCall, Hsl, Layer, Rgba, RichText, draw_rect, point_in_rect},
+    rng::GameRng,
 };
 
 pub const SLOTS_ORIGIN_X: u16 = 7;
@@ -10,7 +14,13 @@ pub const SLOTS_ORIGIN_Y: u16 = 5;
 pub const SLOTS_COLUMNS_X_SPACING: u16 = 4;
 pub const SLOTS_MAX_COLUMN_COUNT: u16 = 6;
 pub const SLOTS_NEIGHBOR_ROW_COUNT: i16 = 3;
+/// Reels can't be thinned below this, so every column keeps some variety.
+pub const MIN_REEL_SIZE: usize = 12;
+/// Upper bound on reel size, which is what the reel editor grid fits.
+pub const MAX_REEL_SIZE: usize = 80;
+pub const MAX_CARD_WEIGHT: u8 = 4;
 
+#[derive(Clone, Copy, Debug)]
 pub enum SlotMachineState {
     Idle,
     Spinning,
@@ -30,13 +40,66 @@ pub struct SlotMachine {
     pub columns: Vec<SlotMachineColumn>,
 }
 
+/// A card on a reel, occupying `weight` consecutive stops of the reel strip.
+#[derive(Clone, Copy)]
+pub struct ReelCard {
+    pub card: Card,
+    pub weight: u8,
+}
+
 #[derive(Clone)]
 pub struct SlotMachineColumn {
     pub cursor: f32,
-    pub cards: Vec<Card>,
+    pub cards: Vec<ReelCard>,
     pub spin_duration: f32,
     pub spin_time_remaining: f32,
     pub spin_speed: f32,
+    /// Ranks luck will try to nudge the column to once it stops.
+    pub lucky_ranks: Vec<Rank>,
+    /// Rows the column got nudged by luck after stopping, already part of `cursor` and only
+    /// kept to mark the column until the next spin.
+    pub luck_nudge: i16,
+}
+
+pub fn shuffle_slot_machine(slot_machine: &mut SlotMachine, rng: &mut GameRng) {
+    for column in slot_machine.columns.iter_mut() {
+        column.cards.shuffle(rng);
+    }
+}
+
+/// Checks a reel against `MIN_REEL_SIZE`, `MAX_REEL_SIZE` and weights of 1 up to
+/// `MAX_CARD_WEIGHT`. Reel edits and loaded saves both have to pass it.
+pub fn check_reel_limits(cards: &[ReelCard]) -> Result<(), &'static str> {
+    if cards.len() < MIN_REEL_SIZE {
+        return Err("Reel is below the minimum size");
+    }
+    if cards.len() > MAX_REEL_SIZE {
+        return Err("Reel is above the maximum size");
+    }
+    if cards
+        .iter()
+        .any(|reel_card| !(1..=MAX_CARD_WEIGHT).contains(&reel_card.weight))
+    {
+        return Err("Reel card weight is out of range");
+    }
+
+    Ok(())
+}
+
+pub fn build_reel(cards: Vec<Card>) -> Vec<ReelCard> {
+    cards
+        .into_iter()
+        .map(|card| ReelCard { card, weight: 1 })
+        .collect()
+}
+
+/// Amount of stops on the reel strip, heavier cards take up more of them.
+pub fn column_strip_len(column: &SlotMachineColumn) -> i16 {
+    column
+        .cards
+        .iter()
+        .map(|reel_card| reel_card.weight as i16)
+        .sum()
 }
 
 pub fn spin_cost(spin_count: i32) -> i32 {
@@ -107,6 +170,7 @@ pub fn spin_slots_column(column: &mut SlotMachineColumn, dt: f32, max_spin_speed
     let spin_stopped: bool = column.spin_speed == 0.0;
     if spin_stopped {
         column.spin_time_remaining = 0.0;
+        nudge_column_towards_lucky_ranks(column);
     }
 }
 
@@ -114,11 +178,24 @@ pub fn slots_are_spinning(slots: &SlotMachine) -> bool {
     slots.columns.iter().all(|column| column.spin_speed == 0.0)
 }
 
+/// Maps the reel strip stop at `row_offset` from the center row to the index of the card on it.
 pub fn get_column_card_index(row_offset: i16, column: &SlotMachineColumn) -> usize {
-    let cards_len: i16 = column.cards.len() as i16;
-    let index: i16 = column.cursor as i16 + row_offset;
-    let wrapped_index: i16 = index.rem_euclid(cards_len);
-    wrapped_index as usize
+    let strip_len: i16 = column_strip_len(column);
+    let stop: i16 = column.cursor as i16 + row_offset;
+    let mut wrapped_stop: i16 = stop.rem_euclid(strip_len);
+
+    for (index, reel_card) in column.cards.iter().enumerate() {
+        if wrapped_stop < reel_card.weight as i16 {
+            return index;
+        }
+        wrapped_stop -= reel_card.weight as i16;
+    }
+
+    unreachable!("Wrapped stop is always within the strip")
+}
+
+pub fn get_column_card(row_offset: i16, column: &SlotMachineColumn) -> &Card {
+    &column.cards[get_column_card_index(row_offset, column)].card
 }
 
 /// Slot columns are supposed to be drawn on top of this.
@@ -146,6 +223,7 @@ pub fn draw_slots_panel(draw_queue: &mut Vec<DrawCall>, x: u16, y: u16, w: u16,
             x,
             y: y + slots_row_index,
             rich_text: RichText::new(" ".repeat(w.into())).with_fg(fg).with_bg(bg),
+            layer: Layer::default(),
         });
     }
 
@@ -194,11 +272,7 @@ pub fn draw_slots(draw_queue: &mut Vec<DrawCall>, x: u16, y: u16, ctx: &Context)
             );
 
             if is_hovering {
-                let card_index = get_column_card_index(0, column);
-                column
-                    .cards
-                    .get(card_index)
-                    .map(|card| (column_index, card))
+                Some((column_index, get_column_card(0, column)))
             } else {
                 None
             }
@@ -289,6 +363,7 @@ pub fn draw_slots(draw_queue: &mut Vec<DrawCall>, x: u16, y: u16, ctx: &Context)
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn draw_column(
     draw_queue: &mut Vec<DrawCall>,
     x: u16,
@@ -301,8 +376,7 @@ fn draw_column(
     _status_highlight: Option<CardStatusHighlight>,
 ) {
     for row_offset in -SLOTS_NEIGHBOR_ROW_COUNT..SLOTS_NEIGHBOR_ROW_COUNT + 1 {
-        let card_index: usize = get_column_card_index(row_offset, column);
-        let card: &Card = &column.cards[card_index];
+        let card: &Card = get_column_card(row_offset, column);
 
         // If `y` is ever negative, the slots are drawn too high up, in which case that's a developer mistake.
         // `debug_assert!` is fine here as the code should never ship with the described case.
@@ -348,6 +422,10 @@ fn draw_column(
                     card_draw_calls.rich_text.bg.lerp(highlight_color, 1.0);
             }
 
+            if column.luck_nudge != 0 {
+                card_draw_calls.rich_text.bg = card_draw_calls.rich_text.bg.lerp(LUCK_COLOR, 0.5);
+            }
+
             if cant_fit_in_hand {
                 card_draw_calls.rich_text.fg =
                     card_draw_calls.rich_text.fg.lerp(will_not_fit_color, 0.2);
@@ -396,10 +474,12 @@ pub fn slots_center_row_indexes_matching_card(target_card: &Card, ctx: &Context)
         .iter()
         .enumerate()
         .filter_map(|(col_idx, column)| {
-            let center_card_index: usize = get_column_card_index(0, column);
-            let card: &Card = &column.cards[center_card_index];
+            let card: &Card = get_column_card(0, column);
 
-            let ranks_match: bool = card.rank == target_card.rank;
+            // Wild ranks have no rank to match by
+            let ranks_match: bool = card.rank == target_card.rank
+                && !card.has_wild_rank()
+                && !target_card.has_wild_rank();
             // let suits_match: bool = card.suit == target_card.suit;
 
             if ranks_match { Some(col_idx) } else { None }
@@ -0,0 +1,51 @@
+use crate::renderer::{Cell, ScreenBuffer, TextAttributes};
+
+/// Readable dump of a `ScreenBuffer` for golden file tests. The characters come first,
+/// then the colors as runs of equally styled cells per row.
+pub fn snapshot_screen_buffer(buf: &ScreenBuffer) -> String {
+    let width: usize = buf.width as usize;
+    let rows: Vec<&[Cell]> = buf.cells.chunks(width).collect();
+
+    let mut lines: Vec<String> = vec![format!("size {}x{}", buf.width, buf.height)];
+
+    // Rows are fenced so trailing spaces survive editors
+    lines.push("--- text".to_string());
+    for row in &rows {
+        lines.push(format!(
+            "|{}|",
+            row.iter().map(Cell::printed_text).collect::<String>()
+        ));
+    }
+
+    lines.push("--- styles".to_string());
+    for (y, row) in rows.iter().enumerate() {
+        let mut run_start: usize = 0;
+
+        for x in 1..=row.len() {
+            let run_continues: bool = x < row.len() && row[x].same_style(&row[run_start]);
+            if run_continues {
+                continue;
+            }
+
+            let cell: &Cell = &row[run_start];
+            let mut line: String = format!(
+                "y={y} x={run_start}-{} fg=#{:06x} bg=#{:06x}{}",
+                x - 1,
+                cell.fg,
+                cell.bg,
+                if cell.bold { " bold" } else { "" }
+            );
+            for flag in TextAttributes::iter().filter(|flag| cell.attributes.contains(*flag)) {
+                line.push(' ');
+                line.push_str(flag.repr());
+            }
+            if let Some(underline_color) = cell.underline_color {
+                line.push_str(&format!(" ul=#{underline_color:06x}"));
+            }
+            lines.push(line);
+            run_start = x;
+        }
+    }
+
+    lines.join("\n") + "\n"
+}
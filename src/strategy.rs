@@ -0,0 +1,245 @@
+use crossterm::event::{Event, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use rand::{Rng, seq::IndexedRandom};
+
+use crate::{
+    button::{Button, ButtonId},
+    card::Card,
+    context::Context,
+    hand::{HAND_CARD_X_SPACING, HAND_ORIGIN_X, HAND_ORIGIN_Y},
+    hint::table_sized_subsets,
+    poker_hand::{PokerHand, eval_poker_hand},
+    rng::{GameRng, build_rng},
+    run::{ROUND_SPIN_BUDGET, RunPhase, can_afford_spin},
+    scoring::{build_score_breakdown, scoring_in_progress},
+    slot_machine::{
+        SLOTS_COLUMNS_X_SPACING, SLOTS_ORIGIN_X, SLOTS_ORIGIN_Y, SlotMachineState, get_column_card,
+        slots_center_row_indexes_matching_card, spin_cost,
+    },
+    table::{TABLE_CARD_X_SPACING, TABLE_ORIGIN_X, TABLE_ORIGIN_Y},
+};
+
+/// Plays the game in headless simulations. Strategies act like a player would,
+/// by sending input events against the buttons of the current tick.
+pub trait Strategy {
+    fn act(&mut self, ctx: &Context, buttons: &[Button]) -> Vec<Event>;
+}
+
+pub const STRATEGY_NAMES: [&str; 2] = ["greedy", "random"];
+
+pub fn build_strategy(name: &str, seed: u64) -> Option<Box<dyn Strategy>> {
+    match name {
+        "greedy" => Some(Box::new(GreedyStrategy::default())),
+        "random" => Some(Box::new(RandomStrategy {
+            rng: build_rng(seed),
+        })),
+        _ => None,
+    }
+}
+
+fn mouse_event(kind: MouseEventKind, x: u16, y: u16) -> Event {
+    Event::Mouse(MouseEvent {
+        kind,
+        column: x,
+        row: y,
+        modifiers: KeyModifiers::NONE,
+    })
+}
+
+fn left_click(x: u16, y: u16) -> Vec<Event> {
+    vec![
+        mouse_event(MouseEventKind::Moved, x, y),
+        mouse_event(MouseEventKind::Down(MouseButton::Left), x, y),
+        mouse_event(MouseEventKind::Up(MouseButton::Left), x, y),
+    ]
+}
+
+/// Right clicking a card sends it between hand and table.
+fn right_click(x: u16, y: u16) -> Vec<Event> {
+    vec![
+        mouse_event(MouseEventKind::Moved, x, y),
+        mouse_event(MouseEventKind::Down(MouseButton::Right), x, y),
+    ]
+}
+
+fn click_button(ctx: &Context, buttons: &[Button], id: ButtonId) -> Vec<Event> {
+    buttons
+        .iter()
+        .find(|button| button.id == id && (button.enabled_when)(ctx))
+        .map_or(vec![], |button| left_click(button.x, button.y))
+}
+
+fn hand_card_position(index: usize) -> (u16, u16) {
+    (
+        HAND_ORIGIN_X + index as u16 * HAND_CARD_X_SPACING,
+        HAND_ORIGIN_Y,
+    )
+}
+
+fn table_card_position(index: usize) -> (u16, u16) {
+    (
+        TABLE_ORIGIN_X + index as u16 * TABLE_CARD_X_SPACING,
+        TABLE_ORIGIN_Y,
+    )
+}
+
+/// Spins until the hand is full or the spins run out, then plays the best scoring cards.
+/// Takes the reel card with the most matches after each spin and buys whatever the shop
+/// offers as long as the next round's spins stay affordable.
+#[derive(Default)]
+pub struct GreedyStrategy {
+    /// Hand cards still to be moved onto the table before playing.
+    planned_cards: Vec<Card>,
+}
+
+impl Strategy for GreedyStrategy {
+    fn act(&mut self, ctx: &Context, buttons: &[Button]) -> Vec<Event> {
+        let slots_spinning: bool = matches!(ctx.slot_machine.state, SlotMachineState::Spinning);
+        if scoring_in_progress(ctx) || slots_spinning {
+            return vec![];
+        }
+
+        match ctx.run.phase {
+            RunPhase::Shop => return shop_greedily(ctx, buttons),
+            RunPhase::GameOver | RunPhase::Victory => return vec![],
+            RunPhase::Playing => {}
+        }
+
+        if !self.planned_cards.is_empty() {
+            let card: Card = self.planned_cards.remove(0);
+            return match ctx
+                .hand_card_slots
+                .iter()
+                .position(|slot| slot.card == Some(card))
+            {
+                Some(index) => {
+                    let (x, y) = hand_card_position(index);
+                    right_click(x, y)
+                }
+                None => {
+                    self.planned_cards.clear();
+                    vec![]
+                }
+            };
+        }
+
+        if ctx.table_card_slots.iter().any(|slot| slot.card.is_some()) {
+            return click_button(ctx, buttons, ButtonId::Play);
+        }
+
+        let hand_cards: Vec<Card> = ctx
+            .hand_card_slots
+            .iter()
+            .filter_map(|slot| slot.card)
+            .collect();
+        let hand_is_full: bool = hand_cards.len() == ctx.hand_card_slots.len();
+
+        match ctx.slot_machine.state {
+            SlotMachineState::PostSpin if !hand_is_full => take_best_column(ctx),
+            SlotMachineState::Idle if !hand_is_full && can_afford_spin(ctx) => {
+                click_button(ctx, buttons, ButtonId::Spin)
+            }
+            _ => {
+                self.planned_cards = best_cards_to_play(ctx, &hand_cards);
+                vec![]
+            }
+        }
+    }
+}
+
+fn take_best_column(ctx: &Context) -> Vec<Event> {
+    let best_column: Option<usize> = (0..ctx.slot_machine.columns.len()).max_by_key(|&index| {
+        let card: &Card = get_column_card(0, &ctx.slot_machine.columns[index]);
+        (
+            slots_center_row_indexes_matching_card(card, ctx).len(),
+            card.rank.coin_value(),
+        )
+    });
+
+    best_column.map_or(vec![], |index| {
+        left_click(
+            SLOTS_ORIGIN_X + index as u16 * SLOTS_COLUMNS_X_SPACING,
+            SLOTS_ORIGIN_Y,
+        )
+    })
+}
+
+fn best_cards_to_play(ctx: &Context, hand_cards: &[Card]) -> Vec<Card> {
+    table_sized_subsets(hand_cards)
+        .into_iter()
+        .max_by_key(|subset| {
+            let cards: Vec<&Card> = subset.iter().collect();
+            let (poker_hand, scoring_cards): (PokerHand, Vec<Card>) = eval_poker_hand(&cards);
+            // Fewer cards keep more in hand for the next play
+            (
+                build_score_breakdown(ctx, poker_hand, &scoring_cards).total(),
+                -(subset.len() as i32),
+            )
+        })
+        .unwrap_or_default()
+}
+
+fn shop_greedily(ctx: &Context, buttons: &[Button]) -> Vec<Event> {
+    let next_round_spin_costs: i32 = (0..ROUND_SPIN_BUDGET)
+        .map(|spin_index| spin_cost(ctx.slot_machine.spin_count + spin_index))
+        .sum();
+    let spare_coins: i32 = ctx.coins - next_round_spin_costs;
+
+    let affordable_item = buttons.iter().find(|button| {
+        let ButtonId::BuyShopItem { index } = button.id else {
+            return false;
+        };
+        let price: Option<i32> = ctx.shop.stock[index]
+            .as_ref()
+            .map(|item| item.price(ctx.run.round));
+        price.is_some_and(|price| price <= spare_coins) && (button.enabled_when)(ctx)
+    });
+
+    match affordable_item {
+        Some(button) => left_click(button.x, button.y),
+        None => click_button(ctx, buttons, ButtonId::LeaveShop),
+    }
+}
+
+/// Clicks a random enabled button or moves a random card every few ticks.
+pub struct RandomStrategy {
+    rng: GameRng,
+}
+
+impl Strategy for RandomStrategy {
+    fn act(&mut self, ctx: &Context, buttons: &[Button]) -> Vec<Event> {
+        const ACTION_CHANCE: f64 = 0.1;
+
+        if !self.rng.random_bool(ACTION_CHANCE) {
+            return vec![];
+        }
+
+        let enabled_buttons: Vec<&Button> = buttons
+            .iter()
+            .filter(|button| (button.enabled_when)(ctx))
+            .collect();
+
+        let card_positions: Vec<(u16, u16)> = ctx
+            .hand_card_slots
+            .iter()
+            .enumerate()
+            .filter(|(_, slot)| slot.card.is_some())
+            .map(|(index, _)| hand_card_position(index))
+            .chain(
+                ctx.table_card_slots
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, slot)| slot.card.is_some())
+                    .map(|(index, _)| table_card_position(index)),
+            )
+            .collect();
+
+        if !card_positions.is_empty() && self.rng.random_bool(0.5) {
+            let &(x, y) = card_positions.choose(&mut self.rng).expect("Not empty");
+            return right_click(x, y);
+        }
+
+        enabled_buttons
+            .choose(&mut self.rng)
+            .map_or(vec![], |button| left_click(button.x, button.y))
+    }
+}
@@ -3,7 +3,9 @@ use crate::{
     card_ops::{CardDragAndDropLocation, CardDragState},
     constants::CARD_SLOT_COLOR,
     context::Context,
-    renderer::{DrawCall, Hsl, Rgba, RichText, draw_rect},
+    hint::{HINT_COLOR, is_hinted},
+    renderer::{DrawCall, Hsl, Layer, Rgba, RichText, draw_rect},
+    scoring::{CHIPS_COLOR, ScoreSource, is_scoring_source_active, scoring_in_progress},
 };
 
 pub const TABLE_ORIGIN_X: u16 = 9;
@@ -11,15 +13,12 @@ pub const TABLE_ORIGIN_Y: u16 = 14;
 pub const TABLE_CARD_X_SPACING: u16 = 4;
 pub const TABLE_SLOT_COUNT: u16 = 5;
 
-pub fn draw_table(draw_queue: &mut Vec<DrawCall>, x: u16, y: u16, ctx: &Context) {
+pub fn draw_table(draw_queue: &mut Vec<DrawCall>, ctx: &Context) {
     let slots_with_cards = ctx
         .table_card_slots
         .iter()
         .enumerate()
-        .filter_map(|(index, slot)| match &slot.card {
-            Some(card) => Some((index, slot, card)),
-            None => None,
-        });
+        .filter_map(|(index, slot)| slot.card.as_ref().map(|card| (index, slot, card)));
 
     for (index, slot, card) in slots_with_cards {
         let is_being_dragged = matches!(ctx.mouse.card_drag,
@@ -35,6 +34,8 @@ pub fn draw_table(draw_queue: &mut Vec<DrawCall>, x: u16, y: u16, ctx: &Context)
             continue;
         }
 
+        let is_scoring: bool = is_scoring_source_active(ctx, ScoreSource::Table(index));
+        let is_hinted: bool = is_hinted(ctx, &CardDragAndDropLocation::Table { index });
         let mut draw_calls: Vec<DrawCall> =
             draw_calls_playing_card_big(slot.x as i16, slot.y as i16, card);
 
@@ -47,17 +48,24 @@ pub fn draw_table(draw_queue: &mut Vec<DrawCall>, x: u16, y: u16, ctx: &Context)
 
             dc.rich_text.fg = fg_hsl.into();
             dc.rich_text.bg = bg_hsl.into();
+
+            if is_scoring {
+                dc.rich_text.bg = dc.rich_text.bg.lerp(CHIPS_COLOR, 0.5);
+            }
+
+            if is_hinted {
+                dc.rich_text.bg = dc.rich_text.bg.lerp(HINT_COLOR, 0.4);
+            }
         }
 
         draw_queue.extend(draw_calls)
     }
 }
 
-pub fn draw_table_card_slots(draw_queue: &mut Vec<DrawCall>, x: u16, y: u16) {
+pub fn draw_table_card_slots(draw_queue: &mut Vec<DrawCall>, x: u16, y: u16, ctx: &Context) {
     for slot_index in 0..TABLE_SLOT_COUNT {
         let local_x: u16 = x + slot_index * TABLE_CARD_X_SPACING;
-        // let locked: bool = !matches!(ctx.slots.state, SlotsState::Idle);
-        let locked: bool = false;
+        let locked: bool = scoring_in_progress(ctx);
 
         let bg_color: Rgba = if locked {
             let light_red: Rgba = Rgba::from_u8(90, 0, 0, CARD_SLOT_COLOR.a);
@@ -82,6 +90,7 @@ pub fn draw_table_card_slots(draw_queue: &mut Vec<DrawCall>, x: u16, y: u16) {
                 rich_text: RichText::new("X")
                     .with_fg(Rgba::from_u8(153, 30, 30, 1.0))
                     .with_bold(true),
+                layer: Layer::default(),
             });
         }
     }
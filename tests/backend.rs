@@ -0,0 +1,51 @@
+//! Bytes the crossterm backend sends to the terminal.
+
+use term_slots::{
+    backend::{Backend, CrosstermBackend},
+    color_depth::ColorDepth,
+    renderer::{DrawCall, Rgba, RichText, ScreenBuffer, compose_buffer, draw_text},
+};
+
+/// Output for one run of bright red text on dark blue at `color_depth`.
+fn drawn_bytes(color_depth: ColorDepth) -> String {
+    let mut buffer: ScreenBuffer = ScreenBuffer::new(2, 1, (0, 0, 0));
+    let mut draw_queue: Vec<DrawCall> = vec![];
+    draw_text(
+        &mut draw_queue,
+        0,
+        0,
+        RichText::new("ab")
+            .with_fg(Rgba::from_u8(255, 0, 0, 1.0))
+            .with_bg(Rgba::from_u8(0, 0, 238, 1.0)),
+    );
+    compose_buffer(&mut buffer, &draw_queue);
+
+    let mut backend: CrosstermBackend<Vec<u8>> =
+        CrosstermBackend::new(vec![]).with_color_depth(color_depth);
+    backend
+        .draw_run(0, 0, &buffer.cells)
+        .expect("Vec writes can't fail");
+    backend.flush().expect("Vec writes can't fail");
+
+    String::from_utf8(backend.writer_mut().clone()).expect("Output is UTF-8")
+}
+
+#[test]
+fn ansi_16_colors_use_plain_sgr_codes() {
+    let output: String = drawn_bytes(ColorDepth::Ansi16);
+
+    assert!(output.contains("\x1b[91m"));
+    assert!(output.contains("\x1b[44m"));
+    assert!(!output.contains("38;5;"));
+    assert!(!output.contains("48;5;"));
+    // Both cells share a style, so the colors are only sent once
+    assert_eq!(output.matches("\x1b[91m").count(), 1);
+}
+
+#[test]
+fn ansi_256_colors_use_palette_indexes() {
+    let output: String = drawn_bytes(ColorDepth::Ansi256);
+
+    assert!(output.contains("\x1b[38;5;196m"));
+    assert!(output.contains("\x1b[48;5;"));
+}
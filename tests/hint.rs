@@ -0,0 +1,137 @@
+//! Best play search behind the hint.
+
+use crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use term_slots::{
+    card::{Card, Rank, Suit},
+    card_ops::CardDragAndDropLocation,
+    context::Context,
+    hand::{HAND_ORIGIN_X, HAND_ORIGIN_Y},
+    hint::{Hint, find_best_play},
+    input::resolve_input,
+    poker_hand::PokerHand,
+    table::{TABLE_ORIGIN_X, TABLE_ORIGIN_Y},
+};
+
+fn context_with_cards(table_cards: &[Card], hand_cards: &[Card]) -> Context {
+    let mut ctx: Context = Context::default();
+    for (slot, card) in ctx.table_card_slots.iter_mut().zip(table_cards) {
+        slot.card = Some(*card);
+    }
+    for (slot, card) in ctx.hand_card_slots.iter_mut().zip(hand_cards) {
+        slot.card = Some(*card);
+    }
+    ctx
+}
+
+fn hand_locations(indexes: &[usize]) -> Vec<CardDragAndDropLocation> {
+    indexes
+        .iter()
+        .map(|&index| CardDragAndDropLocation::Hand { index })
+        .collect()
+}
+
+#[test]
+fn hint_finds_a_flush_split_across_table_and_hand() {
+    let ctx: Context = context_with_cards(
+        &[Card::new(Suit::Heart, Rank::Num9)],
+        &[
+            Card::new(Suit::Spade, Rank::King),
+            Card::new(Suit::Heart, Rank::Num2),
+            Card::new(Suit::Club, Rank::King),
+            Card::new(Suit::Heart, Rank::Num5),
+            Card::new(Suit::Heart, Rank::Jack),
+            Card::new(Suit::Heart, Rank::Num7),
+        ],
+    );
+
+    let hint: Hint = find_best_play(&ctx).expect("Cards to play");
+
+    assert_eq!(hint.poker_hand, PokerHand::Flush);
+    let mut expected_locations: Vec<CardDragAndDropLocation> =
+        vec![CardDragAndDropLocation::Table { index: 0 }];
+    expected_locations.extend(hand_locations(&[1, 3, 4, 5]));
+    assert!(hint.locations == expected_locations);
+}
+
+#[test]
+fn hint_prefers_fewer_cards_for_the_same_payout() {
+    // A lone pair pays the same with or without an unscored card next to it
+    let ctx: Context = context_with_cards(
+        &[],
+        &[
+            Card::new(Suit::Spade, Rank::Queen),
+            Card::new(Suit::Heart, Rank::Num3),
+            Card::new(Suit::Club, Rank::Queen),
+        ],
+    );
+
+    let hint: Hint = find_best_play(&ctx).expect("Cards to play");
+
+    assert_eq!(hint.poker_hand, PokerHand::Pair);
+    assert!(hint.locations == hand_locations(&[0, 2]));
+}
+
+#[test]
+fn hint_is_none_without_cards() {
+    assert!(find_best_play(&Context::default()).is_none());
+}
+
+fn mouse(kind: MouseEventKind, column: u16, row: u16) -> Event {
+    Event::Mouse(MouseEvent {
+        kind,
+        column,
+        row,
+        modifiers: KeyModifiers::NONE,
+    })
+}
+
+#[test]
+fn applying_a_hint_mid_drag_leaves_the_drag_alone() {
+    let queen: Card = Card::new(Suit::Spade, Rank::Queen);
+    let mut ctx: Context = context_with_cards(
+        &[],
+        &[
+            queen,
+            Card::new(Suit::Heart, Rank::Num3),
+            Card::new(Suit::Club, Rank::Queen),
+        ],
+    );
+    let (hand_x, table_x): (u16, u16) = (HAND_ORIGIN_X + 1, TABLE_ORIGIN_X + 1);
+
+    // Picks up the first hand card, asks for the hint and drops the card on the table
+    let events: Vec<Event> = vec![
+        mouse(MouseEventKind::Moved, hand_x, HAND_ORIGIN_Y + 1),
+        mouse(
+            MouseEventKind::Down(MouseButton::Left),
+            hand_x,
+            HAND_ORIGIN_Y + 1,
+        ),
+        Event::Key(KeyEvent::new(KeyCode::Char('H'), KeyModifiers::SHIFT)),
+        mouse(
+            MouseEventKind::Drag(MouseButton::Left),
+            table_x,
+            TABLE_ORIGIN_Y + 1,
+        ),
+        mouse(
+            MouseEventKind::Up(MouseButton::Left),
+            table_x,
+            TABLE_ORIGIN_Y + 1,
+        ),
+    ];
+    for event in events {
+        resolve_input(&mut ctx, event, &[]);
+    }
+
+    assert!(ctx.table_card_slots[0].card == Some(queen));
+    assert!(ctx.table_card_slots[1].card.is_none());
+    assert!(ctx.hand_card_slots[0].card.is_none());
+    assert_eq!(
+        ctx.hand_card_slots
+            .iter()
+            .filter(|slot| slot.card.is_some())
+            .count(),
+        2
+    );
+}
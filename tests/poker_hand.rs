@@ -0,0 +1,157 @@
+//! Poker hand evaluation, mostly how wild cards resolve.
+
+use term_slots::{
+    card::{Card, Rank, Suit, WildKind},
+    poker_hand::{PokerHand, eval_poker_hand},
+};
+
+fn wild(suit: Suit, rank: Rank, wild_kind: WildKind) -> Card {
+    Card {
+        wild: Some(wild_kind),
+        ..Card::new(suit, rank)
+    }
+}
+
+fn eval(cards: &[Card]) -> (PokerHand, Vec<Card>) {
+    let cards: Vec<&Card> = cards.iter().collect();
+    eval_poker_hand(&cards)
+}
+
+fn sorted_ranks(cards: &[Card]) -> Vec<Rank> {
+    let mut ranks: Vec<Rank> = cards.iter().map(|card| card.rank).collect();
+    ranks.sort();
+    ranks
+}
+
+#[test]
+fn fixed_cards_are_left_as_they_are() {
+    let (poker_hand, scoring_cards) = eval(&[
+        Card::new(Suit::Spade, Rank::Num9),
+        Card::new(Suit::Heart, Rank::Num9),
+        Card::new(Suit::Club, Rank::Num4),
+    ]);
+
+    assert_eq!(poker_hand, PokerHand::Pair);
+    assert_eq!(sorted_ranks(&scoring_cards), vec![Rank::Num9, Rank::Num9]);
+}
+
+#[test]
+fn wild_ranks_make_five_of_a_kind() {
+    let (poker_hand, scoring_cards) = eval(&[
+        Card::new(Suit::Spade, Rank::King),
+        Card::new(Suit::Heart, Rank::King),
+        Card::new(Suit::Club, Rank::King),
+        wild(Suit::Diamond, Rank::Num2, WildKind::Any),
+        wild(Suit::Heart, Rank::Num3, WildKind::Rank),
+    ]);
+
+    assert_eq!(poker_hand, PokerHand::FiveOfAKind);
+    assert!(scoring_cards.iter().all(|card| card.rank == Rank::King));
+}
+
+#[test]
+fn five_of_a_kind_leaves_extra_wilds_out() {
+    // Ranks are counted exactly, a sixth king would break the hand
+    let (poker_hand, scoring_cards) = eval(&[
+        Card::new(Suit::Spade, Rank::King),
+        wild(Suit::Heart, Rank::Num2, WildKind::Rank),
+        wild(Suit::Club, Rank::Num2, WildKind::Rank),
+        wild(Suit::Diamond, Rank::Num2, WildKind::Rank),
+        wild(Suit::Spade, Rank::Num2, WildKind::Rank),
+        wild(Suit::Heart, Rank::Num2, WildKind::Rank),
+    ]);
+
+    assert_eq!(poker_hand, PokerHand::FiveOfAKind);
+    assert_eq!(scoring_cards.len(), 5);
+}
+
+#[test]
+fn all_wild_cards_make_a_flush_five_of_aces() {
+    let cards: Vec<Card> = (0..5)
+        .map(|_| wild(Suit::Club, Rank::Num2, WildKind::Any))
+        .collect();
+
+    let (poker_hand, scoring_cards) = eval(&cards);
+
+    assert_eq!(poker_hand, PokerHand::FlushFive);
+    assert!(scoring_cards.iter().all(|card| card.rank == Rank::Ace));
+}
+
+#[test]
+fn wild_ranks_split_into_a_flush_house() {
+    // Four kings in a flush only make Four of a Kind, three and two make a Flush House
+    let (poker_hand, scoring_cards) = eval(&[
+        Card::new(Suit::Spade, Rank::King),
+        Card::new(Suit::Spade, Rank::King),
+        Card::new(Suit::Spade, Rank::Queen),
+        wild(Suit::Spade, Rank::Num2, WildKind::Rank),
+        wild(Suit::Spade, Rank::Num3, WildKind::Rank),
+    ]);
+
+    assert_eq!(poker_hand, PokerHand::FlushHouse);
+    assert_eq!(scoring_cards.len(), 5);
+    assert!(scoring_cards.iter().all(|card| card.suit == Suit::Spade));
+}
+
+#[test]
+fn wild_suit_completes_a_flush() {
+    let (poker_hand, scoring_cards) = eval(&[
+        Card::new(Suit::Heart, Rank::Num2),
+        Card::new(Suit::Heart, Rank::Num5),
+        Card::new(Suit::Heart, Rank::Num9),
+        Card::new(Suit::Heart, Rank::Jack),
+        wild(Suit::Club, Rank::Num7, WildKind::Suit),
+    ]);
+
+    assert_eq!(poker_hand, PokerHand::Flush);
+    assert!(scoring_cards.iter().all(|card| card.suit == Suit::Heart));
+    assert!(scoring_cards.iter().any(|card| card.rank == Rank::Num7));
+}
+
+#[test]
+fn wild_ranks_fill_straight_gaps() {
+    let (poker_hand, scoring_cards) = eval(&[
+        Card::new(Suit::Spade, Rank::Num9),
+        Card::new(Suit::Heart, Rank::Num7),
+        Card::new(Suit::Club, Rank::Num5),
+        wild(Suit::Diamond, Rank::Num2, WildKind::Rank),
+        wild(Suit::Heart, Rank::Num2, WildKind::Rank),
+    ]);
+
+    assert_eq!(poker_hand, PokerHand::Straight);
+    assert_eq!(
+        sorted_ranks(&scoring_cards),
+        vec![Rank::Num9, Rank::Num8, Rank::Num7, Rank::Num6, Rank::Num5]
+    );
+}
+
+#[test]
+fn wild_rank_completes_an_ace_high_straight() {
+    let (poker_hand, scoring_cards) = eval(&[
+        Card::new(Suit::Spade, Rank::Num10),
+        Card::new(Suit::Heart, Rank::Jack),
+        Card::new(Suit::Club, Rank::Queen),
+        Card::new(Suit::Diamond, Rank::King),
+        wild(Suit::Heart, Rank::Num4, WildKind::Rank),
+    ]);
+
+    assert_eq!(poker_hand, PokerHand::Straight);
+    assert!(scoring_cards.iter().any(|card| card.rank == Rank::Ace));
+}
+
+#[test]
+fn wild_ranks_prefer_an_ace_low_straight_over_three_aces() {
+    let (poker_hand, scoring_cards) = eval(&[
+        Card::new(Suit::Spade, Rank::Ace),
+        Card::new(Suit::Heart, Rank::Num2),
+        Card::new(Suit::Club, Rank::Num4),
+        wild(Suit::Diamond, Rank::King, WildKind::Rank),
+        wild(Suit::Heart, Rank::King, WildKind::Rank),
+    ]);
+
+    assert_eq!(poker_hand, PokerHand::Straight);
+    assert_eq!(
+        sorted_ranks(&scoring_cards),
+        vec![Rank::Ace, Rank::Num5, Rank::Num4, Rank::Num3, Rank::Num2]
+    );
+}
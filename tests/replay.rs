@@ -0,0 +1,78 @@
+//! Replay files, which have to give back exactly what was recorded.
+
+use std::{env, fs, path::PathBuf, process};
+
+use crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use term_slots::replay::{
+    Replay, ReplayFrame, finish_recording, load_replay, next_replay_frame, record_frame,
+    start_recording,
+};
+
+fn mouse(kind: MouseEventKind, column: u16, row: u16) -> Event {
+    Event::Mouse(MouseEvent {
+        kind,
+        column,
+        row,
+        modifiers: KeyModifiers::NONE,
+    })
+}
+
+#[test]
+fn replay_round_trips_through_its_file() {
+    let path: PathBuf = env::temp_dir().join(format!("term-slots-replay-{}.txt", process::id()));
+
+    let frames: Vec<(f32, Vec<Event>)> = vec![
+        (1.0 / 144.0, vec![]),
+        (
+            0.016_667,
+            vec![
+                mouse(MouseEventKind::Moved, 12, 7),
+                mouse(MouseEventKind::Down(MouseButton::Left), 12, 7),
+                mouse(MouseEventKind::Drag(MouseButton::Right), 0, 39),
+            ],
+        ),
+        (
+            0.1,
+            vec![
+                // `:` separates the fields of an event
+                Event::Key(KeyEvent::new_with_kind(
+                    KeyCode::Char(':'),
+                    KeyModifiers::SHIFT,
+                    KeyEventKind::Press,
+                )),
+                Event::Key(KeyEvent::new_with_kind(
+                    KeyCode::F(5),
+                    KeyModifiers::CONTROL | KeyModifiers::ALT,
+                    KeyEventKind::Release,
+                )),
+                Event::Key(KeyEvent::new_with_kind(
+                    KeyCode::Esc,
+                    KeyModifiers::NONE,
+                    KeyEventKind::Repeat,
+                )),
+                Event::Resize(80, 24),
+                Event::FocusLost,
+            ],
+        ),
+    ];
+
+    let mut recorder = start_recording(&path, 0xDEAD_BEEF).expect("Temp dir is writable");
+    for (dt, events) in &frames {
+        record_frame(&mut recorder, *dt, events).expect("Temp dir is writable");
+    }
+    finish_recording(recorder).expect("Temp dir is writable");
+
+    let loaded: std::io::Result<Replay> = load_replay(&path);
+    fs::remove_file(&path).expect("Replay file exists");
+    let mut replay: Replay = loaded.expect("Recorded replay loads");
+
+    assert_eq!(replay.seed, 0xDEAD_BEEF);
+    for (dt, events) in frames {
+        let frame: ReplayFrame = next_replay_frame(&mut replay).expect("Every frame is kept");
+        assert_eq!(frame.dt, dt);
+        assert_eq!(frame.events, events);
+    }
+    assert!(next_replay_frame(&mut replay).is_none());
+}
@@ -0,0 +1,158 @@
+//! Save encoding and the checks a loaded save has to pass.
+
+use std::io::ErrorKind;
+
+use rand::RngCore;
+use term_slots::{
+    card::{Card, Enhancement, Rank, Suit, WildKind},
+    context::{Context, ImpulseId},
+    poker_hand::PokerHand,
+    reel_editor::ReelEditor,
+    relic::Relic,
+    rng::reseed,
+    run::RunPhase,
+    save::{decode_save, encode_save, save_game},
+    shop::ShopItem,
+    slot_machine::{MAX_REEL_SIZE, MIN_REEL_SIZE, shuffle_slot_machine},
+};
+
+fn saved_context() -> Context {
+    let mut ctx: Context = Context::default();
+    reseed(&mut ctx, 7);
+    shuffle_slot_machine(&mut ctx.slot_machine, &mut ctx.rng);
+    ctx
+}
+
+/// The save with the reel cards of the first column passed through `edit`.
+fn edit_first_reel(ctx: &Context, edit: impl Fn(&mut Vec<String>)) -> String {
+    let mut edited: bool = false;
+
+    encode_save(ctx)
+        .lines()
+        .map(|line| {
+            if edited || !line.starts_with("column ") {
+                return line.to_string();
+            }
+            edited = true;
+
+            // `column` and six column fields come before the cards
+            let mut tokens: Vec<String> = line.split_whitespace().map(str::to_string).collect();
+            let mut cards: Vec<String> = tokens.split_off(7);
+            edit(&mut cards);
+            tokens.extend(cards);
+            tokens.join(" ")
+        })
+        .map(|line| line + "\n")
+        .collect()
+}
+
+fn assert_invalid_data(text: &str) {
+    let error = decode_save(text).err().expect("Save is refused");
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn save_round_trips() {
+    let mut ctx: Context = saved_context();
+    ctx.score = 1234;
+    ctx.coins = 56;
+    ctx.luck = 3;
+    ctx.luck_armed = true;
+    ctx.run.phase = RunPhase::Shop;
+    ctx.run.round = 4;
+    ctx.relics = vec![Relic::PiggyBank, Relic::GoldenTooth];
+    ctx.hand_levels.insert(PokerHand::Flush, 3);
+    ctx.hand_play_counts.insert(PokerHand::Pair, 7);
+    ctx.shop.stock = vec![Some(ShopItem::HandSlot), None];
+    ctx.hand_card_slots[1].card = Some(Card {
+        enhancement: Some(Enhancement::Glass),
+        wild: Some(WildKind::Rank),
+        ..Card::new(Suit::Heart, Rank::Queen)
+    });
+    ctx.table_card_slots[0].card = Some(Card::new(Suit::Club, Rank::Num10));
+    ctx.slot_machine.columns[2].cards[0].weight = 3;
+    ctx.reel_editor = Some(ReelEditor {
+        column_index: 1,
+        selected_index: Some(4),
+        new_card: Card::new(Suit::Diamond, Rank::Num7),
+    });
+    // Moves the rng along, a resumed run has to continue from the same spot
+    ctx.rng.next_u64();
+
+    let text: String = encode_save(&ctx);
+    let mut loaded: Context = decode_save(&text).expect("Save decodes");
+
+    assert_eq!(encode_save(&loaded), text);
+    assert_eq!(loaded.score, 1234);
+    assert_eq!(loaded.run.phase, RunPhase::Shop);
+    assert!(loaded.hand_card_slots[1].card == ctx.hand_card_slots[1].card);
+    assert_eq!(loaded.rng.next_u64(), ctx.rng.next_u64());
+}
+
+#[test]
+fn untouched_save_decodes() {
+    assert!(decode_save(&edit_first_reel(&saved_context(), |_| {})).is_ok());
+}
+
+#[test]
+fn zero_card_weight_is_refused() {
+    assert_invalid_data(&edit_first_reel(&saved_context(), |cards| {
+        let (card, _) = cards[0].rsplit_once('/').expect("Card has a weight");
+        cards[0] = format!("{card}/0");
+    }));
+}
+
+#[test]
+fn reel_below_minimum_size_is_refused() {
+    assert_invalid_data(&edit_first_reel(&saved_context(), |cards| {
+        cards.truncate(MIN_REEL_SIZE - 1);
+    }));
+}
+
+#[test]
+fn reel_above_maximum_size_is_refused() {
+    assert_invalid_data(&edit_first_reel(&saved_context(), |cards| {
+        let card: String = cards[0].clone();
+        cards.resize(MAX_REEL_SIZE + 1, card);
+    }));
+}
+
+#[test]
+fn reel_editor_outside_the_reels_is_refused() {
+    let mut ctx: Context = saved_context();
+    ctx.reel_editor = Some(ReelEditor {
+        column_index: ctx.slot_machine.columns.len(),
+        selected_index: None,
+        new_card: Card::new(Suit::Spade, Rank::Ace),
+    });
+
+    assert_invalid_data(&encode_save(&ctx));
+}
+
+#[test]
+fn reel_editor_selection_outside_the_reel_is_refused() {
+    let mut ctx: Context = saved_context();
+    ctx.reel_editor = Some(ReelEditor {
+        column_index: 0,
+        selected_index: Some(ctx.slot_machine.columns[0].cards.len()),
+        new_card: Card::new(Suit::Spade, Rank::Ace),
+    });
+
+    assert_invalid_data(&encode_save(&ctx));
+}
+
+#[test]
+fn save_key_leaves_the_save_alone_when_it_has_to() {
+    let mut prompting: Context = saved_context();
+    prompting.resume_prompt_open = true;
+    let mut disabled: Context = saved_context();
+    disabled.save_disabled = true;
+
+    for mut ctx in [prompting, disabled] {
+        save_game(&mut ctx);
+
+        // Nothing was written, so neither outcome is flashed
+        assert!(!ctx.impulse_timestamps.contains_key(&ImpulseId::GameSaved));
+        assert!(!ctx.impulse_timestamps.contains_key(&ImpulseId::SaveFailed));
+    }
+}
@@ -0,0 +1,122 @@
+//! Chips × mult scoring of played hands.
+
+use term_slots::{
+    card::{Card, Enhancement, Rank, Suit},
+    context::Context,
+    poker_hand::{PokerHand, eval_poker_hand},
+    scoring::{
+        ScoreBreakdown, ScoreSource, ScoreStep, ScoringAnimation, build_score_breakdown,
+        finish_scoring,
+    },
+};
+
+fn enhanced(suit: Suit, rank: Rank, enhancement: Enhancement) -> Card {
+    Card {
+        enhancement: Some(enhancement),
+        ..Card::new(suit, rank)
+    }
+}
+
+#[test]
+fn breakdown_of_an_enhanced_pair() {
+    let mut ctx: Context = Context::default();
+    let table_cards: [Card; 3] = [
+        enhanced(Suit::Spade, Rank::King, Enhancement::Foil),
+        enhanced(Suit::Heart, Rank::King, Enhancement::Glass),
+        enhanced(Suit::Club, Rank::Num3, Enhancement::Stone),
+    ];
+    let hand_cards: [Card; 2] = [
+        enhanced(Suit::Diamond, Rank::Num5, Enhancement::Gold),
+        enhanced(Suit::Spade, Rank::Num9, Enhancement::Steel),
+    ];
+    for (slot, card) in ctx.table_card_slots.iter_mut().zip(table_cards) {
+        slot.card = Some(card);
+    }
+    for (slot, card) in ctx.hand_card_slots.iter_mut().zip(hand_cards) {
+        slot.card = Some(card);
+    }
+
+    let cards: Vec<&Card> = table_cards.iter().collect();
+    let (poker_hand, scoring_cards): (PokerHand, Vec<Card>) = eval_poker_hand(&cards);
+    let breakdown: ScoreBreakdown = build_score_breakdown(&ctx, poker_hand, &scoring_cards);
+
+    assert_eq!(poker_hand, PokerHand::Pair);
+    let step = |source: ScoreSource| -> (i32, f32) {
+        let found: &ScoreStep = breakdown
+            .steps
+            .iter()
+            .find(|step| step.source == source)
+            .expect("Source has a step");
+        (found.chips_added, found.mult_factor)
+    };
+
+    // Pair base, king + foil, king + glass, stone played, gold and steel held
+    assert_eq!(breakdown.steps.len(), 6);
+    assert_eq!(step(ScoreSource::PokerHand), (5, 1.0));
+    assert_eq!(step(ScoreSource::Table(0)), (30, 1.0));
+    assert_eq!(step(ScoreSource::Table(1)), (10, 1.5));
+    assert_eq!(step(ScoreSource::Table(2)), (30, 1.0));
+    assert_eq!(step(ScoreSource::Hand(0)), (0, 1.0));
+    assert_eq!(step(ScoreSource::Hand(1)), (0, 1.25));
+
+    assert_eq!(breakdown.chips(), 75);
+    assert_eq!(breakdown.mult(), 1.875);
+    assert_eq!(breakdown.total(), 141);
+    assert_eq!(breakdown.payout(), 149);
+}
+
+#[test]
+fn breakdown_adds_hand_levels_to_the_base() {
+    let mut ctx: Context = Context::default();
+    ctx.hand_levels.insert(PokerHand::Pair, 3);
+    let cards: [Card; 2] = [
+        Card::new(Suit::Spade, Rank::Num4),
+        Card::new(Suit::Heart, Rank::Num4),
+    ];
+
+    let breakdown: ScoreBreakdown = build_score_breakdown(&ctx, PokerHand::Pair, &cards);
+
+    // 5 base + 2 levels of 5, then both fours
+    assert_eq!(breakdown.chips(), 15 + 4 + 4);
+    assert_eq!(breakdown.total(), 23);
+}
+
+#[test]
+fn held_gold_pays_coins_outside_the_score() {
+    let mut ctx: Context = Context::default();
+    let cards: [Card; 2] = [
+        Card::new(Suit::Spade, Rank::Num4),
+        Card::new(Suit::Heart, Rank::Num4),
+    ];
+    for (slot, card) in ctx.table_card_slots.iter_mut().zip(cards) {
+        slot.card = Some(card);
+    }
+    ctx.hand_card_slots[2].card = Some(enhanced(Suit::Club, Rank::Num7, Enhancement::Gold));
+
+    let breakdown: ScoreBreakdown = build_score_breakdown(&ctx, PokerHand::Pair, &cards);
+
+    // Pair base, both fours, then the gold card's coins on the side
+    let gold_step: &ScoreStep = breakdown.steps.last().expect("Breakdown has steps");
+    assert!(gold_step.source == ScoreSource::Hand(2));
+    assert_eq!(
+        (
+            gold_step.chips_added,
+            gold_step.mult_factor,
+            gold_step.coins_added
+        ),
+        (0, 1.0, 8)
+    );
+    assert_eq!(breakdown.total(), 13);
+    assert_eq!(breakdown.bonus_coins(), 8);
+    assert_eq!(gold_step.label(), "+$8");
+
+    let (coins_before, score_before): (i32, i32) = (ctx.coins, ctx.score);
+    ctx.scoring = Some(ScoringAnimation {
+        breakdown,
+        elapsed: 0.0,
+    });
+    finish_scoring(&mut ctx);
+
+    assert_eq!(ctx.coins - coins_before, 13 + 8);
+    assert_eq!(ctx.score - score_before, 13);
+}
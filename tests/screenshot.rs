@@ -0,0 +1,44 @@
+//! HTML and SVG screenshots of a screen buffer.
+
+use term_slots::{
+    renderer::{DrawCall, Rgba, RichText, ScreenBuffer, compose_buffer, draw_text},
+    screenshot::{screen_buffer_to_html, screen_buffer_to_svg},
+};
+
+/// Red on blue text that is both dim and reversed.
+fn dim_reversed_buffer() -> ScreenBuffer {
+    let mut buffer: ScreenBuffer = ScreenBuffer::new(3, 1, (0, 0, 0));
+    let mut draw_queue: Vec<DrawCall> = vec![];
+    draw_text(
+        &mut draw_queue,
+        0,
+        0,
+        RichText::new("abc")
+            .with_fg(Rgba::from_u8(255, 0, 0, 1.0))
+            .with_bg(Rgba::from_u8(0, 0, 255, 1.0))
+            .with_dim(true)
+            .with_reverse(true),
+    );
+    compose_buffer(&mut buffer, &draw_queue);
+    buffer
+}
+
+#[test]
+fn html_keeps_raw_colors_with_style_classes() {
+    let html: String = screen_buffer_to_html(&dim_reversed_buffer());
+
+    assert!(
+        html.contains("<span class=\"dim reverse\" style=\"--fg:#ff0000;--bg:#0000ff\">abc</span>")
+    );
+    assert!(html.contains(".dim.reverse{"));
+}
+
+#[test]
+fn svg_swaps_fills_for_reverse_and_fades_dim_text() {
+    let svg: String = screen_buffer_to_svg(&dim_reversed_buffer());
+
+    assert!(svg.contains("fill=\"#ff0000\"/>"));
+    assert!(svg.contains("fill=\"#0000ff\" fill-opacity=\"0.5\">a</text>"));
+    // No blended colors anywhere
+    assert_eq!(svg.matches("fill=\"#").count(), 4);
+}
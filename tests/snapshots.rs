@@ -0,0 +1,261 @@
+//! Golden snapshot tests for rendered frames. Run with `UPDATE_GOLDEN=1` to rewrite the
+//! files in `tests/golden` after an intended change, then review the diff.
+
+use std::{env, fs, path::PathBuf};
+
+use term_slots::{
+    TERM_SCREEN_HEIGHT, TERM_SCREEN_WIDTH,
+    backend::MemoryBackend,
+    card::standard_52_deck,
+    context::{Context, ImpulseId},
+    game::tick,
+    renderer::{
+        CellRun, DrawCall, Layer, Rgba, RichText, ScreenBuffer, blend_source_over, compose_buffer,
+        diff_buffers, draw_in_layer, draw_rect, draw_text,
+    },
+    rng::reseed,
+    slot_machine::{SLOTS_ORIGIN_X, SLOTS_ORIGIN_Y, SlotMachineState, shuffle_slot_machine},
+    snapshot::snapshot_screen_buffer,
+};
+
+const SNAPSHOT_SEED: u64 = 1;
+
+fn assert_golden(name: &str, snapshot: &str) {
+    let path: PathBuf = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("golden")
+        .join(format!("{name}.txt"));
+
+    if env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::create_dir_all(path.parent().expect("Has a parent")).expect("Golden dir is writable");
+        fs::write(&path, snapshot).expect("Golden file is writable");
+        return;
+    }
+
+    let expected: String = fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!(
+            "Missing golden file {}, run with UPDATE_GOLDEN=1 to create it",
+            path.display()
+        )
+    });
+
+    if let Some((line_index, (expected_line, actual_line))) = expected
+        .lines()
+        .zip(snapshot.lines())
+        .enumerate()
+        .find(|(_, (expected_line, actual_line))| expected_line != actual_line)
+    {
+        panic!(
+            "Snapshot `{name}` differs at line {}\nexpected: {expected_line}\nactual:   {actual_line}\nrun with UPDATE_GOLDEN=1 if this is intended",
+            line_index + 1
+        );
+    }
+    assert_eq!(
+        expected.lines().count(),
+        snapshot.lines().count(),
+        "Snapshot `{name}` changed length, run with UPDATE_GOLDEN=1 if this is intended"
+    );
+}
+
+/// Seeded and with the animated background and vignette off, so frames are stable and
+/// the style runs stay readable.
+fn snapshot_context() -> Context {
+    let mut ctx: Context = Context::default();
+    reseed(&mut ctx, SNAPSHOT_SEED);
+    shuffle_slot_machine(&mut ctx.slot_machine, &mut ctx.rng);
+    ctx.settings.bg_shader_enabled = false;
+    ctx.settings.vignette_enabled = false;
+    ctx
+}
+
+/// Goes through the real render path and returns what a terminal would show.
+fn render(ctx: &mut Context) -> ScreenBuffer {
+    let mut backend: MemoryBackend =
+        MemoryBackend::new(TERM_SCREEN_WIDTH, TERM_SCREEN_HEIGHT, (0, 0, 0));
+    tick(ctx, 0.0, vec![], &mut backend).expect("Memory backend can't fail");
+    backend.grid
+}
+
+fn fill_hand(ctx: &mut Context) {
+    for (slot, card) in ctx.hand_card_slots.iter_mut().zip(standard_52_deck()) {
+        slot.card = Some(card);
+    }
+}
+
+#[test]
+fn idle_screen() {
+    let mut ctx: Context = snapshot_context();
+    assert_golden("idle", &snapshot_screen_buffer(&render(&mut ctx)));
+}
+
+#[test]
+fn post_spin_highlight() {
+    let mut ctx: Context = snapshot_context();
+    ctx.slot_machine.state = SlotMachineState::PostSpin;
+    ctx.mouse.x = SLOTS_ORIGIN_X;
+    ctx.mouse.y = SLOTS_ORIGIN_Y;
+
+    assert_golden(
+        "post_spin_highlight",
+        &snapshot_screen_buffer(&render(&mut ctx)),
+    );
+}
+
+#[test]
+fn full_hand() {
+    let mut ctx: Context = snapshot_context();
+    fill_hand(&mut ctx);
+
+    assert_golden("full_hand", &snapshot_screen_buffer(&render(&mut ctx)));
+}
+
+#[test]
+fn no_space_impulse_mid_animation() {
+    let mut ctx: Context = snapshot_context();
+    fill_hand(&mut ctx);
+    ctx.slot_machine.state = SlotMachineState::PostSpin;
+    ctx.game_time = 10.0;
+    ctx.impulse_timestamps
+        .insert(ImpulseId::NoSpaceInHandHint, ctx.game_time - 0.3);
+
+    assert_golden(
+        "no_space_impulse",
+        &snapshot_screen_buffer(&render(&mut ctx)),
+    );
+}
+
+#[test]
+fn compose_alpha_blending() {
+    let mut buffer: ScreenBuffer = ScreenBuffer::new(12, 3, (0, 0, 0));
+    let mut draw_queue: Vec<DrawCall> = vec![];
+
+    draw_rect(&mut draw_queue, 0, 0, 8, 3, Rgba::from_u8(200, 40, 40, 1.0));
+    draw_text(
+        &mut draw_queue,
+        1,
+        0,
+        RichText::new("opaque").with_bold(true),
+    );
+    draw_text(&mut draw_queue, 1, 1, RichText::new("tinted"));
+    // A translucent rect blends into the bg and tints the text underneath
+    draw_rect(&mut draw_queue, 4, 0, 8, 2, Rgba::from_u8(40, 40, 200, 0.5));
+    // Text with a transparent bg keeps the bg it's drawn over
+    draw_text(
+        &mut draw_queue,
+        3,
+        2,
+        RichText::new("on top").with_fg(Rgba::from_u8(255, 230, 80, 1.0)),
+    );
+
+    compose_buffer(&mut buffer, &draw_queue);
+    assert_golden("compose_alpha_blending", &snapshot_screen_buffer(&buffer));
+}
+
+#[test]
+fn compose_wide_and_combining_glyphs() {
+    let mut buffer: ScreenBuffer = ScreenBuffer::new(12, 4, (0, 0, 0));
+    let mut draw_queue: Vec<DrawCall> = vec![];
+
+    draw_text(&mut draw_queue, 0, 0, RichText::new("a漢字b"));
+    draw_text(&mut draw_queue, 0, 1, RichText::new("e\u{301}x🎰y"));
+    // Overwriting either half of a wide glyph blanks the other half
+    draw_text(&mut draw_queue, 0, 2, RichText::new("漢字漢字"));
+    draw_text(&mut draw_queue, 1, 2, RichText::new("|"));
+    draw_text(&mut draw_queue, 4, 2, RichText::new("-"));
+    // Cut off by the right edge
+    draw_text(&mut draw_queue, 11, 3, RichText::new("漢"));
+
+    compose_buffer(&mut buffer, &draw_queue);
+    assert_golden("compose_wide_glyphs", &snapshot_screen_buffer(&buffer));
+}
+
+#[test]
+fn compose_text_attributes() {
+    let mut buffer: ScreenBuffer = ScreenBuffer::new(8, 3, (0, 0, 0));
+    let mut draw_queue: Vec<DrawCall> = vec![];
+
+    draw_text(
+        &mut draw_queue,
+        0,
+        0,
+        RichText::new("ital").with_italic(true).with_dim(true),
+    );
+    draw_text(
+        &mut draw_queue,
+        4,
+        0,
+        RichText::new("ul").with_underline_color(Rgba::from_u8(255, 0, 0, 1.0)),
+    );
+    draw_text(
+        &mut draw_queue,
+        0,
+        1,
+        RichText::new("rev").with_reverse(true).with_blink(true),
+    );
+    // Text drawn over attributed text replaces the attributes too
+    draw_text(
+        &mut draw_queue,
+        0,
+        2,
+        RichText::new("strike").with_strikethrough(true),
+    );
+    draw_text(&mut draw_queue, 3, 2, RichText::new("x"));
+
+    compose_buffer(&mut buffer, &draw_queue);
+    assert_golden("compose_text_attributes", &snapshot_screen_buffer(&buffer));
+}
+
+#[test]
+fn compose_stacks_layers_regardless_of_push_order() {
+    let mut buffer: ScreenBuffer = ScreenBuffer::new(4, 1, (0, 0, 0));
+    let mut draw_queue: Vec<DrawCall> = vec![];
+
+    draw_in_layer(&mut draw_queue, Layer::Overlay, |draw_queue| {
+        draw_text(draw_queue, 0, 0, RichText::new("o"));
+        // Nested calls keep the higher layer
+        draw_in_layer(draw_queue, Layer::Tooltip, |draw_queue| {
+            draw_text(draw_queue, 3, 0, RichText::new("n"));
+        });
+    });
+    draw_in_layer(&mut draw_queue, Layer::Tooltip, |draw_queue| {
+        draw_text(draw_queue, 0, 0, RichText::new("tt"));
+    });
+    draw_text(&mut draw_queue, 0, 0, RichText::new("ssss"));
+
+    compose_buffer(&mut buffer, &draw_queue);
+    let text: String = buffer.cells.iter().map(|cell| cell.ch).collect();
+    assert_eq!(text, "otsn");
+}
+
+#[test]
+fn diff_redraws_wide_glyph_for_changed_continuation() {
+    let mut old: ScreenBuffer = ScreenBuffer::new(4, 1, (0, 0, 0));
+    let mut new: ScreenBuffer = ScreenBuffer::new(4, 1, (0, 0, 0));
+    let mut draw_queue: Vec<DrawCall> = vec![];
+
+    draw_text(&mut draw_queue, 0, 0, RichText::new("漢"));
+    compose_buffer(&mut old, &draw_queue);
+
+    // Only the continuation cell's bg changes
+    draw_rect(&mut draw_queue, 1, 0, 1, 1, Rgba::from_u8(200, 40, 40, 1.0));
+    compose_buffer(&mut new, &draw_queue);
+
+    let runs: Vec<CellRun> = diff_buffers(&old, &new);
+    assert_eq!(runs.len(), 1);
+    assert_eq!((runs[0].x, runs[0].cells.len()), (0, 2));
+}
+
+#[test]
+fn blend_source_over_mixes_by_alpha() {
+    let black: Rgba = Rgba::from_u8(0, 0, 0, 1.0);
+
+    let opaque: Rgba = blend_source_over(&black, &Rgba::from_u8(255, 255, 255, 1.0));
+    assert_eq!(opaque, Rgba::from_u8(255, 255, 255, 1.0));
+
+    let transparent: Rgba = blend_source_over(&black, &Rgba::from_u8(255, 255, 255, 0.0));
+    assert_eq!(transparent, black);
+
+    let half: Rgba = blend_source_over(&black, &Rgba::from_u8(255, 0, 100, 0.5));
+    assert!(half.r.abs_diff(128) <= 1 && half.g == 0 && half.b.abs_diff(50) <= 1);
+    assert_eq!(half.a, 1.0);
+}